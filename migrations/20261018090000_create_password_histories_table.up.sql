@@ -0,0 +1,8 @@
+CREATE TABLE password_histories (
+    id INTEGER NOT NULL PRIMARY KEY,
+    created_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    password TEXT NOT NULL
+);
+
+CREATE INDEX password_histories_user_id_idx ON password_histories(user_id);
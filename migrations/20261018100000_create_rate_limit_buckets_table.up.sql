@@ -0,0 +1,6 @@
+CREATE TABLE rate_limit_buckets (
+    key TEXT NOT NULL PRIMARY KEY,
+    tokens REAL NOT NULL,
+    allowed INTEGER NOT NULL,
+    refilled_at REAL NOT NULL
+);
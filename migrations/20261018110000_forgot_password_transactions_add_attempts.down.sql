@@ -0,0 +1,2 @@
+ALTER TABLE forgot_password_transactions DROP COLUMN invalidated_at;
+ALTER TABLE forgot_password_transactions DROP COLUMN attempts;
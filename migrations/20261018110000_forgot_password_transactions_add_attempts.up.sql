@@ -0,0 +1,7 @@
+ALTER TABLE forgot_password_transactions ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE forgot_password_transactions ADD COLUMN invalidated_at DATETIME;
+
+-- Tokens are now stored hashed, so open transactions holding plaintext tokens can no longer be used.
+UPDATE forgot_password_transactions
+SET invalidated_at = (DATETIME('subsec'))
+WHERE used_at IS NULL;
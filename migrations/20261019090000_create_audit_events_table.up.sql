@@ -0,0 +1,13 @@
+CREATE TABLE audit_events (
+    id INTEGER NOT NULL PRIMARY KEY,
+    created_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    event_type TEXT NOT NULL,
+    user_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    ip_address TEXT,
+    details TEXT
+);
+
+CREATE INDEX audit_events_user_id_idx ON audit_events(user_id);
+CREATE INDEX audit_events_event_type_idx ON audit_events(event_type);
+CREATE INDEX audit_events_created_at_idx ON audit_events(created_at);
@@ -0,0 +1,3 @@
+DROP TABLE notification_opt_outs;
+DROP TABLE known_devices;
+DROP TABLE notifications;
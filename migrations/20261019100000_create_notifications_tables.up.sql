@@ -0,0 +1,31 @@
+-- Invitations go to people who do not have an account yet, so a notification is addressed to
+-- either a user or an email.
+CREATE TABLE notifications (
+    id INTEGER NOT NULL PRIMARY KEY,
+    created_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    email TEXT,
+    notification_type TEXT NOT NULL,
+    details TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    sent_at DATETIME,
+    CHECK (user_id IS NOT NULL OR email IS NOT NULL)
+);
+
+CREATE INDEX notifications_pending_idx ON notifications(sent_at, attempts);
+
+CREATE TABLE known_devices (
+    id INTEGER NOT NULL PRIMARY KEY,
+    created_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    last_seen_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    fingerprint TEXT NOT NULL,
+    UNIQUE (user_id, fingerprint)
+);
+
+CREATE TABLE notification_opt_outs (
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    notification_type TEXT NOT NULL,
+    PRIMARY KEY (user_id, notification_type)
+);
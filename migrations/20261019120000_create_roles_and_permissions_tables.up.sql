@@ -0,0 +1,34 @@
+CREATE TABLE roles (
+    id INTEGER NOT NULL PRIMARY KEY,
+    created_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    name TEXT NOT NULL UNIQUE,
+    parent_id INTEGER REFERENCES roles(id) ON DELETE SET NULL
+);
+
+CREATE TABLE permissions (
+    id INTEGER NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE role_permissions (
+    role_id INTEGER NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+    permission_id INTEGER NOT NULL REFERENCES permissions(id) ON DELETE CASCADE,
+    PRIMARY KEY (role_id, permission_id)
+);
+
+-- A role inherits every permission of its parent.
+INSERT INTO roles (name, parent_id) VALUES ('User', NULL);
+INSERT INTO roles (name, parent_id) VALUES ('Admin', (SELECT id FROM roles WHERE name = 'User'));
+
+INSERT INTO permissions (name, description) VALUES
+    ('users:read', 'List all users.'),
+    ('users:impersonate', 'Sign in as another user.'),
+    ('audit:read', 'Read the audit log.'),
+    ('notifications:manage', 'Manage own notification preferences.');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE (r.name = 'User' AND p.name IN ('notifications:manage'))
+   OR (r.name = 'Admin' AND p.name IN ('users:read', 'users:impersonate', 'audit:read'));
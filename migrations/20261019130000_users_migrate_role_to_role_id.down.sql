@@ -0,0 +1,12 @@
+DROP TRIGGER users_default_role;
+DROP INDEX users_role_id_idx;
+
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT User;
+UPDATE users SET role = (SELECT r.name FROM roles r WHERE r.id = users.role_id);
+ALTER TABLE users DROP COLUMN role_id;
+
+DELETE FROM role_permissions
+WHERE permission_id = (SELECT id FROM permissions WHERE name = 'roles:manage');
+DELETE FROM permissions WHERE name = 'roles:manage';
+
+ALTER TABLE roles DROP COLUMN is_system;
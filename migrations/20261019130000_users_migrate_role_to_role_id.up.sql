@@ -0,0 +1,27 @@
+ALTER TABLE roles ADD COLUMN is_system BOOLEAN NOT NULL DEFAULT 0;
+UPDATE roles SET is_system = 1 WHERE name IN ('User', 'Admin');
+
+INSERT INTO permissions (name, description)
+VALUES ('roles:manage', 'Manage roles and assign them to users.');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.name = 'Admin' AND p.name = 'roles:manage';
+
+-- Role names that are in use but unknown become custom roles inheriting from User.
+INSERT INTO roles (name, parent_id)
+SELECT DISTINCT u.role, (SELECT id FROM roles WHERE name = 'User')
+FROM users u
+WHERE u.role NOT IN (SELECT name FROM roles);
+
+ALTER TABLE users ADD COLUMN role_id INTEGER REFERENCES roles(id);
+UPDATE users SET role_id = (SELECT r.id FROM roles r WHERE r.name = users.role);
+ALTER TABLE users DROP COLUMN role;
+
+CREATE INDEX users_role_id_idx ON users(role_id);
+
+CREATE TRIGGER users_default_role AFTER INSERT ON users WHEN NEW.role_id IS NULL
+BEGIN
+    UPDATE users SET role_id = (SELECT id FROM roles WHERE name = 'User') WHERE id = NEW.id;
+END;
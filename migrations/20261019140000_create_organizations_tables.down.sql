@@ -0,0 +1,3 @@
+ALTER TABLE sessions DROP COLUMN active_organization_id;
+DROP TABLE organization_members;
+DROP TABLE organizations;
@@ -0,0 +1,18 @@
+CREATE TABLE organizations (
+    id INTEGER NOT NULL PRIMARY KEY,
+    created_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE organization_members (
+    organization_id INTEGER NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    role TEXT NOT NULL DEFAULT Member,
+    PRIMARY KEY (organization_id, user_id)
+);
+
+CREATE INDEX organization_members_user_id_idx ON organization_members(user_id);
+
+ALTER TABLE sessions ADD COLUMN active_organization_id INTEGER REFERENCES organizations(id) ON DELETE SET NULL;
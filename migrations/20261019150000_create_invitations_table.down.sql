@@ -0,0 +1,5 @@
+DELETE FROM role_permissions
+WHERE permission_id = (SELECT id FROM permissions WHERE name = 'invitations:manage');
+DELETE FROM permissions WHERE name = 'invitations:manage';
+
+DROP TABLE invitations;
@@ -0,0 +1,26 @@
+CREATE TABLE invitations (
+    id INTEGER NOT NULL PRIMARY KEY,
+    created_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    email TEXT NOT NULL,
+    token TEXT NOT NULL,
+    role_id INTEGER REFERENCES roles(id) ON DELETE SET NULL,
+    organization_id INTEGER REFERENCES organizations(id) ON DELETE CASCADE,
+    organization_role TEXT,
+    invited_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    expires_at DATETIME NOT NULL,
+    accepted_at DATETIME,
+    accepted_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    revoked_at DATETIME
+);
+
+CREATE UNIQUE INDEX invitations_token_uc ON invitations(token);
+CREATE INDEX invitations_email_idx ON invitations(email);
+CREATE INDEX invitations_organization_id_idx ON invitations(organization_id);
+
+INSERT INTO permissions (name, description)
+VALUES ('invitations:manage', 'Invite users with any role, into any organization.');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.name = 'Admin' AND p.name = 'invitations:manage';
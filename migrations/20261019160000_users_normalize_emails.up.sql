@@ -0,0 +1,13 @@
+-- Emails are now normalized by the application: trimmed, with internationalized domains
+-- converted to punycode and local parts lowercased unless EMAIL_LOWERCASE_LOCAL_PART=false.
+-- SQLite cannot apply the same rules, so existing emails are normalized in Rust after the
+-- migrations run. Accounts whose emails normalize to the same address are left untouched and
+-- reported here so they can be merged or renamed by hand.
+CREATE TABLE user_email_collisions (
+    id INTEGER NOT NULL PRIMARY KEY,
+    created_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email TEXT NOT NULL,
+    normalized_email TEXT NOT NULL,
+    reason TEXT NOT NULL
+);
@@ -0,0 +1,3 @@
+DROP INDEX users_username_uc;
+
+ALTER TABLE users DROP COLUMN username;
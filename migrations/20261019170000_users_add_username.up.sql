@@ -0,0 +1,3 @@
+ALTER TABLE users ADD COLUMN username TEXT;
+
+CREATE UNIQUE INDEX users_username_uc ON users(username COLLATE NOCASE);
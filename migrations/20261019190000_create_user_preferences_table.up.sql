@@ -0,0 +1,7 @@
+CREATE TABLE user_preferences (
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT (DATETIME('subsec')),
+    PRIMARY KEY (user_id, name)
+);
@@ -0,0 +1,2 @@
+-- The seeded admin is intentionally not restored.
+SELECT 1;
@@ -0,0 +1,10 @@
+-- The seeded admin shipped with a publicly known password. It is removed unless its password has
+-- been changed since, the first admin is now created on startup from BOOTSTRAP_ADMIN_EMAIL.
+DELETE FROM sessions
+WHERE user_id IN (SELECT id FROM users WHERE email = 'admin@localhost' AND password = '$argon2id$v=19$m=19456,t=2,p=1$Ng1C0MAYdof4fUod+cpYoA$m68r4KYZwo2oKAIeSkjYBqLBhurs9aY95onYOkiuOoY')
+   OR impersonator_id IN (SELECT id FROM users WHERE email = 'admin@localhost' AND password = '$argon2id$v=19$m=19456,t=2,p=1$Ng1C0MAYdof4fUod+cpYoA$m68r4KYZwo2oKAIeSkjYBqLBhurs9aY95onYOkiuOoY');
+
+DELETE FROM forgot_password_transactions
+WHERE user_id IN (SELECT id FROM users WHERE email = 'admin@localhost' AND password = '$argon2id$v=19$m=19456,t=2,p=1$Ng1C0MAYdof4fUod+cpYoA$m68r4KYZwo2oKAIeSkjYBqLBhurs9aY95onYOkiuOoY');
+
+DELETE FROM users WHERE email = 'admin@localhost' AND password = '$argon2id$v=19$m=19456,t=2,p=1$Ng1C0MAYdof4fUod+cpYoA$m68r4KYZwo2oKAIeSkjYBqLBhurs9aY95onYOkiuOoY';
@@ -2,7 +2,8 @@ use sqlx::SqliteConnection;
 
 use crate::{
     account::entities::forgot_password::{
-        CreateForgotPasswordTransactionEntity, VerifyForgotPasswordTransaction,
+        CreateForgotPasswordTransactionEntity, ForgotPasswordTransactionEntity,
+        VerifyForgotPasswordTransaction,
     },
     core::{
         error::{ApiError, ApiResult},
@@ -10,53 +11,126 @@ use crate::{
     },
 };
 
+pub async fn invalidate_open_requests_for_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+) -> ApiResult<()> {
+    let now = DbDateTime::now();
+    sqlx::query!(
+        "
+        UPDATE forgot_password_transactions
+        SET invalidated_at = ?
+        WHERE user_id = ?
+          AND used_at IS NULL
+          AND invalidated_at IS NULL
+        ",
+        now,
+        user_id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
 pub async fn request_otp(
     connection: &mut SqliteConnection,
     transaction: CreateForgotPasswordTransactionEntity,
-) -> ApiResult<String> {
+) -> ApiResult<()> {
     sqlx::query!(
         "
         INSERT INTO forgot_password_transactions
             (user_id, token, reset_password_token, expires_at)
         VALUES
             (?, ?, ?, ?)
-        RETURNING token
         ",
         transaction.user_id,
         transaction.token,
         transaction.reset_password_token,
         transaction.expires_at,
     )
-    .fetch_one(connection)
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn find_open_request_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    max_attempts: i64,
+) -> ApiResult<Option<ForgotPasswordTransactionEntity>> {
+    let now = DbDateTime::now();
+    sqlx::query_as!(
+        ForgotPasswordTransactionEntity,
+        "
+        SELECT id, user_id, token, attempts
+        FROM forgot_password_transactions
+        WHERE user_id = ?
+          AND ? < expires_at
+          AND attempts < ?
+          AND verified_at IS NULL
+          AND used_at IS NULL
+          AND invalidated_at IS NULL
+        ORDER BY id DESC
+        LIMIT 1
+        ",
+        user_id,
+        now,
+        max_attempts
+    )
+    .fetch_optional(connection)
     .await
-    .map(|result| result.token)
+    .map_err(ApiError::from)
+}
+
+pub async fn record_failed_attempt(
+    connection: &mut SqliteConnection,
+    id: i64,
+    max_attempts: i64,
+) -> ApiResult<()> {
+    let now = DbDateTime::now();
+    sqlx::query!(
+        "
+        UPDATE forgot_password_transactions
+        SET
+            attempts = attempts + 1,
+            invalidated_at = CASE WHEN attempts + 1 >= ? THEN ? ELSE invalidated_at END
+        WHERE id = ?
+        ",
+        max_attempts,
+        now,
+        id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
     .map_err(ApiError::from)
 }
 
 pub async fn verify_otp_request(
     connection: &mut SqliteConnection,
     transaction: VerifyForgotPasswordTransaction,
-) -> ApiResult<Option<String>> {
+) -> ApiResult<bool> {
     let now = DbDateTime::now();
     sqlx::query!(
         "
         UPDATE forgot_password_transactions
-        SET verified_at = ?
-        WHERE user_id = ?
-          AND token = ?
+        SET verified_at = ?, reset_password_token = ?
+        WHERE id = ?
           AND ? < expires_at
           AND verified_at IS NULL
           AND used_at IS NULL
-        RETURNING reset_password_token
+          AND invalidated_at IS NULL
         ",
         now,
-        transaction.user_id,
-        transaction.token,
+        transaction.reset_password_token,
+        transaction.id,
         now
     )
-    .fetch_optional(connection)
+    .execute(connection)
     .await
-    .map(|result| result.map(|result| result.reset_password_token))
+    .map(|result| result.rows_affected() > 0)
     .map_err(ApiError::from)
 }
 
@@ -73,6 +147,7 @@ pub async fn consume_otp_request(
           AND ? < expires_at
           AND verified_at IS NOT NULL
           AND used_at IS NULL
+          AND invalidated_at IS NULL
         RETURNING user_id
         ",
         now,
@@ -1,3 +1,6 @@
 pub mod forgot_password_repository;
+pub mod password_history_repository;
+pub mod preference_repository;
+pub mod role_repository;
 pub mod session_repository;
 pub mod user_repository;
@@ -0,0 +1,68 @@
+use sqlx::SqliteConnection;
+
+use crate::core::error::{ApiError, ApiResult};
+
+pub async fn find_recent_passwords_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    limit: i64,
+) -> ApiResult<Vec<String>> {
+    sqlx::query!(
+        "
+        SELECT password
+        FROM password_histories
+        WHERE user_id = ?
+        ORDER BY id DESC
+        LIMIT ?
+        ",
+        user_id,
+        limit
+    )
+    .fetch_all(connection)
+    .await
+    .map(|results| results.into_iter().map(|result| result.password).collect())
+    .map_err(ApiError::from)
+}
+
+pub async fn create_password_history(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    password: &str,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "INSERT INTO password_histories (user_id, password) VALUES (?, ?)",
+        user_id,
+        password
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn prune_password_history(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    keep: i64,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "
+        DELETE FROM password_histories
+        WHERE user_id = ?
+          AND id NOT IN (
+            SELECT id
+            FROM password_histories
+            WHERE user_id = ?
+            ORDER BY id DESC
+            LIMIT ?
+          )
+        ",
+        user_id,
+        user_id,
+        keep
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
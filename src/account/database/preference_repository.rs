@@ -0,0 +1,67 @@
+use sqlx::SqliteConnection;
+
+use crate::{
+    account::entities::preference::{PreferenceKey, StoredPreferenceEntity},
+    core::{
+        error::{ApiError, ApiResult},
+        types::DbDateTime,
+    },
+};
+
+pub async fn find_preferences_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+) -> ApiResult<Vec<StoredPreferenceEntity>> {
+    sqlx::query_as!(
+        StoredPreferenceEntity,
+        "SELECT name, value FROM user_preferences WHERE user_id = ?",
+        user_id
+    )
+    .fetch_all(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn upsert_preference(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    key: PreferenceKey,
+    value: &str,
+) -> ApiResult<()> {
+    let name = key.to_string();
+    let now = DbDateTime::now();
+    sqlx::query!(
+        "
+        INSERT INTO user_preferences (user_id, name, value, updated_at)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT (user_id, name) DO UPDATE SET
+            value = excluded.value,
+            updated_at = excluded.updated_at
+        ",
+        user_id,
+        name,
+        value,
+        now
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn delete_preference(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    key: PreferenceKey,
+) -> ApiResult<()> {
+    let name = key.to_string();
+    sqlx::query!(
+        "DELETE FROM user_preferences WHERE user_id = ? AND name = ?",
+        user_id,
+        name
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
@@ -0,0 +1,270 @@
+use sqlx::SqliteConnection;
+
+use crate::{
+    account::entities::role::{CreateRoleEntity, PermissionEntity, RoleEntity},
+    core::error::{ApiError, ApiResult},
+};
+
+pub async fn find_roles(connection: &mut SqliteConnection) -> ApiResult<Vec<RoleEntity>> {
+    sqlx::query_as!(
+        RoleEntity,
+        "SELECT id, name, parent_id, is_system FROM roles ORDER BY id"
+    )
+    .fetch_all(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn find_role_by_id(
+    connection: &mut SqliteConnection,
+    id: i64,
+) -> ApiResult<Option<RoleEntity>> {
+    sqlx::query_as!(
+        RoleEntity,
+        "SELECT id, name, parent_id, is_system FROM roles WHERE id = ?",
+        id
+    )
+    .fetch_optional(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn find_role_by_name(
+    connection: &mut SqliteConnection,
+    name: &str,
+) -> ApiResult<Option<RoleEntity>> {
+    sqlx::query_as!(
+        RoleEntity,
+        "SELECT id, name, parent_id, is_system FROM roles WHERE name = ?",
+        name
+    )
+    .fetch_optional(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn role_exists_by_name(
+    connection: &mut SqliteConnection,
+    name: &str,
+    excluded_id: Option<i64>,
+) -> ApiResult<bool> {
+    sqlx::query!(
+        "
+        SELECT COUNT(1) as count
+        FROM roles
+        WHERE name = ? COLLATE NOCASE
+          AND (? IS NULL OR id != ?)
+        ",
+        name,
+        excluded_id,
+        excluded_id
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count > 0)
+    .map_err(ApiError::from)
+}
+
+pub async fn create_role(
+    connection: &mut SqliteConnection,
+    role: CreateRoleEntity,
+) -> ApiResult<RoleEntity> {
+    sqlx::query_as!(
+        RoleEntity,
+        "
+        INSERT INTO roles (name, parent_id)
+        VALUES (?, ?)
+        RETURNING id, name, parent_id, is_system
+        ",
+        role.name,
+        role.parent_id
+    )
+    .fetch_one(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn update_role(
+    connection: &mut SqliteConnection,
+    id: i64,
+    role: CreateRoleEntity,
+) -> ApiResult<RoleEntity> {
+    sqlx::query_as!(
+        RoleEntity,
+        "
+        UPDATE roles
+        SET name = ?, parent_id = ?
+        WHERE id = ?
+        RETURNING id, name, parent_id, is_system
+        ",
+        role.name,
+        role.parent_id,
+        id
+    )
+    .fetch_one(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn delete_role(connection: &mut SqliteConnection, id: i64) -> ApiResult<()> {
+    sqlx::query!("DELETE FROM roles WHERE id = ?", id)
+        .execute(connection)
+        .await
+        .map(|_| ())
+        .map_err(ApiError::from)
+}
+
+pub async fn count_users_by_role_id(
+    connection: &mut SqliteConnection,
+    role_id: i64,
+) -> ApiResult<i64> {
+    sqlx::query!(
+        "SELECT COUNT(1) as count FROM users WHERE role_id = ?",
+        role_id
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count)
+    .map_err(ApiError::from)
+}
+
+pub async fn count_child_roles(connection: &mut SqliteConnection, role_id: i64) -> ApiResult<i64> {
+    sqlx::query!(
+        "SELECT COUNT(1) as count FROM roles WHERE parent_id = ?",
+        role_id
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count)
+    .map_err(ApiError::from)
+}
+
+pub async fn find_permissions(
+    connection: &mut SqliteConnection,
+) -> ApiResult<Vec<PermissionEntity>> {
+    sqlx::query_as!(
+        PermissionEntity,
+        "SELECT name, description FROM permissions ORDER BY name"
+    )
+    .fetch_all(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn find_direct_permissions_by_role_id(
+    connection: &mut SqliteConnection,
+    role_id: i64,
+) -> ApiResult<Vec<String>> {
+    sqlx::query!(
+        "
+        SELECT p.name
+        FROM role_permissions rp
+        INNER JOIN permissions p ON p.id = rp.permission_id
+        WHERE rp.role_id = ?
+        ORDER BY p.name
+        ",
+        role_id
+    )
+    .fetch_all(connection)
+    .await
+    .map(|results| results.into_iter().map(|result| result.name).collect())
+    .map_err(ApiError::from)
+}
+
+// Returns the direct permissions of every role as `(role_id, permission)` pairs.
+pub async fn find_all_direct_permissions(
+    connection: &mut SqliteConnection,
+) -> ApiResult<Vec<(i64, String)>> {
+    sqlx::query!(
+        "
+        SELECT rp.role_id, p.name
+        FROM role_permissions rp
+        INNER JOIN permissions p ON p.id = rp.permission_id
+        ORDER BY rp.role_id, p.name
+        "
+    )
+    .fetch_all(connection)
+    .await
+    .map(|results| {
+        results
+            .into_iter()
+            .map(|result| (result.role_id, result.name))
+            .collect()
+    })
+    .map_err(ApiError::from)
+}
+
+pub async fn replace_role_permissions(
+    connection: &mut SqliteConnection,
+    role_id: i64,
+    permissions: &[String],
+) -> ApiResult<()> {
+    sqlx::query!("DELETE FROM role_permissions WHERE role_id = ?", role_id)
+        .execute(&mut *connection)
+        .await
+        .map_err(ApiError::from)?;
+
+    for permission in permissions {
+        sqlx::query!(
+            "
+            INSERT INTO role_permissions (role_id, permission_id)
+            SELECT ?, id FROM permissions WHERE name = ?
+            ON CONFLICT DO NOTHING
+            ",
+            role_id,
+            permission
+        )
+        .execute(&mut *connection)
+        .await
+        .map_err(ApiError::from)?;
+    }
+
+    Ok(())
+}
+
+// Resolves the permissions of a role together with the ones inherited from its ancestors.
+pub async fn find_permissions_by_role_id(
+    connection: &mut SqliteConnection,
+    role_id: i64,
+) -> ApiResult<Vec<String>> {
+    sqlx::query!(
+        r#"
+        WITH RECURSIVE role_tree(id, parent_id) AS (
+            SELECT r.id, r.parent_id FROM roles r WHERE r.id = ?
+            UNION
+            SELECT r.id, r.parent_id FROM roles r INNER JOIN role_tree t ON r.id = t.parent_id
+        )
+        SELECT DISTINCT p.name as "name!"
+        FROM role_tree t
+        INNER JOIN role_permissions rp ON rp.role_id = t.id
+        INNER JOIN permissions p ON p.id = rp.permission_id
+        "#,
+        role_id
+    )
+    .fetch_all(connection)
+    .await
+    .map(|results| results.into_iter().map(|result| result.name).collect())
+    .map_err(ApiError::from)
+}
+
+// Returns the ids of the role and all of its ancestors.
+pub async fn find_role_ancestry(
+    connection: &mut SqliteConnection,
+    role_id: i64,
+) -> ApiResult<Vec<i64>> {
+    sqlx::query!(
+        r#"
+        WITH RECURSIVE role_tree(id, parent_id) AS (
+            SELECT r.id, r.parent_id FROM roles r WHERE r.id = ?
+            UNION
+            SELECT r.id, r.parent_id FROM roles r INNER JOIN role_tree t ON r.id = t.parent_id
+        )
+        SELECT id as "id!: i64" FROM role_tree
+        "#,
+        role_id
+    )
+    .fetch_all(connection)
+    .await
+    .map(|results| results.into_iter().map(|result| result.id).collect())
+    .map_err(ApiError::from)
+}
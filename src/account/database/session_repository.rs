@@ -24,7 +24,9 @@ pub async fn find_session_by_token_ignoring_expiration(
             token_expiry,
             refresh_token_expiry,
             revoked_at as "revoked_at!: Option<DbDateTime>",
-            revocation_reason as "revocation_reason!: Option<RevocationReason>"
+            revocation_reason as "revocation_reason!: Option<RevocationReason>",
+            impersonator_id,
+            active_organization_id
         FROM sessions
         WHERE token = ?
         AND refresh_token_expiry > ?
@@ -38,6 +40,64 @@ pub async fn find_session_by_token_ignoring_expiration(
     .map_err(ApiError::from)
 }
 
+pub async fn find_session_by_token(
+    connection: &mut SqliteConnection,
+    token: &str,
+) -> ApiResult<Option<SessionEntity>> {
+    let now = DbDateTime::now();
+    sqlx::query_as!(
+        SessionEntity,
+        r#"
+        SELECT
+            id,
+            user_id,
+            token,
+            refresh_token,
+            token_expiry,
+            refresh_token_expiry,
+            revoked_at as "revoked_at!: Option<DbDateTime>",
+            revocation_reason as "revocation_reason!: Option<RevocationReason>",
+            impersonator_id,
+            active_organization_id
+        FROM sessions
+        WHERE token = ?
+        AND token_expiry > ?
+        AND revoked_at IS NULL
+        "#,
+        token,
+        now
+    )
+    .fetch_optional(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn revoke_session_by_id(
+    connection: &mut SqliteConnection,
+    id: i64,
+    reason: RevocationReason,
+) -> ApiResult<()> {
+    let now = DbDateTime::now();
+    sqlx::query!(
+        "
+        UPDATE sessions
+        SET
+            revoked_at = ?,
+            revocation_reason = ?
+        WHERE id = ?
+          AND revoked_at IS NULL
+        ",
+        now,
+        reason,
+        id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+// Impersonation sessions belong to the impersonating admin and end on their own.
 pub async fn revoke_session_for_user_id(
     connection: &mut SqliteConnection,
     user_id: i64,
@@ -52,6 +112,7 @@ pub async fn revoke_session_for_user_id(
             revocation_reason = ?
         WHERE user_id = ?
           AND revoked_at IS NULL
+          AND impersonator_id IS NULL
         ",
         now,
         reason,
@@ -76,9 +137,11 @@ pub async fn create_session(
             token,
             refresh_token,
             token_expiry,
-            refresh_token_expiry
+            refresh_token_expiry,
+            impersonator_id,
+            active_organization_id
         )
-        VALUES (?, ?, ?, ?, ?)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         RETURNING
             id,
             user_id,
@@ -87,15 +150,35 @@ pub async fn create_session(
             token_expiry,
             refresh_token_expiry,
             revoked_at as "revoked_at!: Option<DbDateTime>",
-            revocation_reason as "revocation_reason!: Option<RevocationReason>"
+            revocation_reason as "revocation_reason!: Option<RevocationReason>",
+            impersonator_id,
+            active_organization_id
         "#,
         user_id,
         *session.token(),
         *session.refresh_token(),
         *session.token_expiry(),
         *session.refresh_token_expiry(),
+        *session.impersonator_id(),
+        *session.active_organization_id(),
     )
     .fetch_one(connection)
     .await
     .map_err(ApiError::from)
 }
+
+pub async fn update_active_organization(
+    connection: &mut SqliteConnection,
+    id: i64,
+    organization_id: Option<i64>,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "UPDATE sessions SET active_organization_id = ? WHERE id = ?",
+        organization_id,
+        id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
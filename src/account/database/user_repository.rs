@@ -1,7 +1,15 @@
 use sqlx::{Sqlite, SqliteConnection};
 
 use crate::{
-    account::entities::user::{CreateUserEntity, FailedLoginAttempt, UserEntity, UserRole},
+    account::{
+        entities::{
+            role::DEFAULT_ROLE,
+            user::{
+                CreateUserEntity, FailedLoginAttempt, UpdateProfileEntity, UserEntity, UserFilter,
+            },
+        },
+        error::AccountError,
+    },
     core::{
         error::{ApiError, ApiResult},
         models::{Page, PageRequest},
@@ -9,6 +17,13 @@ use crate::{
     },
 };
 
+const USER_FILTER: &str = "
+    WHERE (?1 IS NULL OR EXISTS (
+        SELECT 1 FROM organization_members m
+        WHERE m.user_id = u.id AND m.organization_id = ?1
+    ))
+    ";
+
 pub async fn find_user_by_id(
     connection: &mut SqliteConnection,
     id: i64,
@@ -19,11 +34,18 @@ pub async fn find_user_by_id(
         SELECT
             u.id,
             u.email,
+            u.username,
+            u.display_name,
+            u.avatar_url,
+            u.locale,
+            u.timezone,
             u.password,
-            u.role as "role: UserRole",
+            u.role_id as "role_id!",
+            r.name as role,
             u.login_attempts,
             u.last_failed_login_attempt as "last_failed_login_attempt!: Option<DbDateTime>"
         FROM users u
+        INNER JOIN roles r ON r.id = u.role_id
         WHERE u.id = ?
         "#,
         id
@@ -43,11 +65,18 @@ pub async fn find_user_by_email(
         SELECT
             u.id,
             u.email,
+            u.username,
+            u.display_name,
+            u.avatar_url,
+            u.locale,
+            u.timezone,
             u.password,
-            u.role as "role: UserRole",
+            u.role_id as "role_id!",
+            r.name as role,
             u.login_attempts,
             u.last_failed_login_attempt as "last_failed_login_attempt!: Option<DbDateTime>"
         FROM users u
+        INNER JOIN roles r ON r.id = u.role_id
         WHERE u.email = ?
         "#,
         email
@@ -57,6 +86,37 @@ pub async fn find_user_by_email(
     .map_err(ApiError::from)
 }
 
+pub async fn find_user_by_username(
+    connection: &mut SqliteConnection,
+    username: &str,
+) -> ApiResult<Option<UserEntity>> {
+    sqlx::query_as!(
+        UserEntity,
+        r#"
+        SELECT
+            u.id,
+            u.email,
+            u.username,
+            u.display_name,
+            u.avatar_url,
+            u.locale,
+            u.timezone,
+            u.password,
+            u.role_id as "role_id!",
+            r.name as role,
+            u.login_attempts,
+            u.last_failed_login_attempt as "last_failed_login_attempt!: Option<DbDateTime>"
+        FROM users u
+        INNER JOIN roles r ON r.id = u.role_id
+        WHERE u.username = ? COLLATE NOCASE
+        "#,
+        username
+    )
+    .fetch_optional(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
 pub async fn find_user_by_token(
     connection: &mut SqliteConnection,
     token: &str,
@@ -68,12 +128,19 @@ pub async fn find_user_by_token(
         SELECT
             u.id,
             u.email,
+            u.username,
+            u.display_name,
+            u.avatar_url,
+            u.locale,
+            u.timezone,
             u.password,
-            u.role as "role: UserRole",
+            u.role_id as "role_id!",
+            r.name as role,
             u.login_attempts,
             u.last_failed_login_attempt as "last_failed_login_attempt!: Option<DbDateTime>"
         FROM sessions s
         INNER JOIN users u ON u.id = s.user_id
+        INNER JOIN roles r ON r.id = u.role_id
         WHERE s.token = ?
           AND s.token_expiry > ?
           AND s.revoked_at IS NULL
@@ -88,31 +155,43 @@ pub async fn find_user_by_token(
 
 pub async fn find_paginated_users(
     connection: &mut SqliteConnection,
+    filter: UserFilter,
     request: PageRequest,
 ) -> ApiResult<Page<UserEntity>> {
-    let sql = request.to_sql_string(
+    let sql = request.to_sql_string(&format!(
         r#"
         SELECT
             u.id,
             u.email,
+            u.username,
+            u.display_name,
+            u.avatar_url,
+            u.locale,
+            u.timezone,
             u.password,
-            u.role,
+            u.role_id,
+            r.name as role,
             u.login_attempts,
             u.last_failed_login_attempt
         FROM users u
-        "#,
-    );
+        INNER JOIN roles r ON r.id = u.role_id
+        {USER_FILTER}
+        "#
+    ));
 
     let users = sqlx::query_as::<Sqlite, UserEntity>(&sql)
+        .bind(filter.organization_id)
         .fetch_all(&mut *connection)
         .await
         .map_err(ApiError::from)?;
 
-    let count = sqlx::query!("SELECT COUNT(1) as count FROM users")
-        .fetch_one(connection)
-        .await
-        .map(|result| result.count as u64)
-        .map_err(ApiError::from)?;
+    let count =
+        sqlx::query_scalar::<Sqlite, i64>(&format!("SELECT COUNT(1) FROM users u {USER_FILTER}"))
+            .bind(filter.organization_id)
+            .fetch_one(connection)
+            .await
+            .map(|count| count as u64)
+            .map_err(ApiError::from)?;
 
     Ok(Page::new(users, count, request))
 }
@@ -131,28 +210,126 @@ pub async fn user_exists_by_email(
     .map_err(ApiError::from)
 }
 
+pub async fn user_exists_by_username(
+    connection: &mut SqliteConnection,
+    username: &str,
+    excluded_id: Option<i64>,
+) -> ApiResult<bool> {
+    sqlx::query!(
+        "
+        SELECT COUNT(1) as count
+        FROM users u
+        WHERE u.username = ? COLLATE NOCASE
+          AND (? IS NULL OR u.id != ?)
+        ",
+        username,
+        excluded_id,
+        excluded_id
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count > 0)
+    .map_err(ApiError::from)
+}
+
+pub async fn user_exists_by_role_name(
+    connection: &mut SqliteConnection,
+    role: &str,
+) -> ApiResult<bool> {
+    sqlx::query!(
+        "
+        SELECT COUNT(1) as count
+        FROM users u
+        JOIN roles r ON r.id = u.role_id
+        WHERE r.name = ?
+        ",
+        role
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count > 0)
+    .map_err(ApiError::from)
+}
+
 pub async fn create_user(
     connection: &mut SqliteConnection,
     user: CreateUserEntity,
 ) -> ApiResult<UserEntity> {
-    sqlx::query_as!(
-        UserEntity,
-        r#"
-        INSERT INTO users (email, password)
-        VALUES (?, ?)
-        RETURNING
-            id,
-            email,
-            password,
-            role as "role!: UserRole",
-            login_attempts,
-            last_failed_login_attempt as "last_failed_login_attempt!: Option<DbDateTime>"
-        "#,
+    let id = sqlx::query!(
+        "
+        INSERT INTO users (email, username, password, role_id)
+        VALUES (?, ?, ?, (SELECT id FROM roles WHERE name = ?))
+        RETURNING id
+        ",
         user.email,
-        user.password
+        user.username,
+        user.password,
+        DEFAULT_ROLE
     )
-    .fetch_one(connection)
+    .fetch_one(&mut *connection)
+    .await
+    .map(|result| result.id)
+    .map_err(ApiError::from)?;
+
+    find_user_by_id(connection, id)
+        .await?
+        .ok_or(ApiError::AccountError(AccountError::UserDoesNotExistById(
+            id,
+        )))
+}
+
+pub async fn update_username_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    username: Option<&str>,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "UPDATE users SET username = ? WHERE id = ?",
+        username,
+        user_id
+    )
+    .execute(connection)
     .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn update_profile_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    profile: UpdateProfileEntity,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "
+        UPDATE users
+        SET display_name = ?, avatar_url = ?, locale = ?, timezone = ?
+        WHERE id = ?
+        ",
+        profile.display_name,
+        profile.avatar_url,
+        profile.locale,
+        profile.timezone,
+        user_id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn update_avatar_url_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    avatar_url: Option<&str>,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "UPDATE users SET avatar_url = ? WHERE id = ?",
+        avatar_url,
+        user_id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
     .map_err(ApiError::from)
 }
 
@@ -192,3 +369,76 @@ pub async fn update_password_by_user_id(
     .map(|_| ())
     .map_err(ApiError::from)
 }
+
+pub async fn update_role_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    role_id: i64,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "UPDATE users SET role_id = ? WHERE id = ?",
+        role_id,
+        user_id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn count_email_collisions(connection: &mut SqliteConnection) -> ApiResult<i64> {
+    sqlx::query!("SELECT COUNT(1) as count FROM user_email_collisions")
+        .fetch_one(connection)
+        .await
+        .map(|result| result.count)
+        .map_err(ApiError::from)
+}
+
+pub async fn find_all_emails(connection: &mut SqliteConnection) -> ApiResult<Vec<(i64, String)>> {
+    sqlx::query!("SELECT id, email FROM users")
+        .fetch_all(connection)
+        .await
+        .map(|rows| rows.into_iter().map(|row| (row.id, row.email)).collect())
+        .map_err(ApiError::from)
+}
+
+pub async fn update_email_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    email: &str,
+) -> ApiResult<()> {
+    sqlx::query!("UPDATE users SET email = ? WHERE id = ?", email, user_id)
+        .execute(connection)
+        .await
+        .map(|_| ())
+        .map_err(ApiError::from)
+}
+
+pub async fn delete_email_collisions(connection: &mut SqliteConnection) -> ApiResult<()> {
+    sqlx::query!("DELETE FROM user_email_collisions")
+        .execute(connection)
+        .await
+        .map(|_| ())
+        .map_err(ApiError::from)
+}
+
+pub async fn create_email_collision(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    email: &str,
+    normalized_email: &str,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "
+        INSERT INTO user_email_collisions (user_id, email, normalized_email, reason)
+        VALUES (?, ?, ?, 'Collision')
+        ",
+        user_id,
+        email,
+        normalized_email
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
@@ -1,9 +1,17 @@
 use chrono::Utc;
 use uuid::Uuid;
 
-use crate::core::{types::DbDateTime, AppConfig};
+use crate::{
+    account::utils::{hash_password, hash_token},
+    core::{error::ApiResult, types::DbDateTime, AppConfig},
+};
 
-const DEFAULT_OTP_LENGTH: usize = 6;
+pub struct ForgotPasswordTransactionEntity {
+    pub id: i64,
+    pub user_id: i64,
+    pub token: String,
+    pub attempts: i64,
+}
 
 pub struct CreateForgotPasswordTransactionEntity {
     pub user_id: i64,
@@ -13,17 +21,18 @@ pub struct CreateForgotPasswordTransactionEntity {
 }
 
 impl CreateForgotPasswordTransactionEntity {
-    pub fn new(config: AppConfig, user_id: i64) -> Self {
-        Self {
+    pub fn new(config: &AppConfig, user_id: i64, otp: &str) -> ApiResult<Self> {
+        Ok(Self {
             user_id,
-            token: crate::account::utils::generate_otp(DEFAULT_OTP_LENGTH),
-            reset_password_token: Uuid::new_v4().to_string(),
+            token: hash_password(&config.argon2, otp)?,
+            // Replaced once the OTP is verified, so the client never sees this token.
+            reset_password_token: hash_token(&Uuid::new_v4().to_string()),
             expires_at: (Utc::now() + config.otp_validity_duration).into(),
-        }
+        })
     }
 }
 
 pub struct VerifyForgotPasswordTransaction {
-    pub user_id: i64,
-    pub token: String,
+    pub id: i64,
+    pub reset_password_token: String,
 }
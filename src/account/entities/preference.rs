@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+#[derive(
+    Clone, Copy, PartialEq, Debug, Serialize, Deserialize, JsonSchema, EnumString, Display,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase", ascii_case_insensitive)]
+pub enum Theme {
+    Light,
+    Dark,
+    System,
+}
+
+#[derive(
+    Clone, Copy, PartialEq, Debug, Serialize, Deserialize, JsonSchema, EnumString, Display,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase", ascii_case_insensitive)]
+pub enum EmailDigest {
+    Off,
+    Daily,
+    Weekly,
+}
+
+// Names under which the preferences are stored, one row per preference the user has changed.
+#[derive(Clone, Copy, EnumString, Display)]
+#[strum(serialize_all = "camelCase")]
+pub enum PreferenceKey {
+    Theme,
+    ReducedMotion,
+    EmailDigest,
+    DesktopNotifications,
+    BetaFeatures,
+}
+
+#[derive(Clone)]
+pub struct PreferencesEntity {
+    pub theme: Theme,
+    pub reduced_motion: bool,
+    pub email_digest: EmailDigest,
+    pub desktop_notifications: bool,
+    pub beta_features: Vec<String>,
+}
+
+pub struct StoredPreferenceEntity {
+    pub name: String,
+    pub value: String,
+}
+
+impl PreferencesEntity {
+    // Applies the stored preferences on top of the defaults. Values that can no longer be read,
+    // e.g. after a preference changed type, fall back to the default.
+    pub fn with_stored(mut self, stored: Vec<StoredPreferenceEntity>) -> Self {
+        for preference in stored {
+            if let Err(err) = self.apply(&preference) {
+                tracing::warn!(
+                    "Ignoring stored preference {} = {}: {err}",
+                    preference.name,
+                    preference.value
+                );
+            }
+        }
+
+        self
+    }
+
+    fn apply(&mut self, preference: &StoredPreferenceEntity) -> Result<(), String> {
+        let value = &preference.value;
+        match PreferenceKey::from_str(&preference.name).map_err(|err| err.to_string())? {
+            PreferenceKey::Theme => self.theme = parse(value)?,
+            PreferenceKey::ReducedMotion => self.reduced_motion = parse(value)?,
+            PreferenceKey::EmailDigest => self.email_digest = parse(value)?,
+            PreferenceKey::DesktopNotifications => self.desktop_notifications = parse(value)?,
+            PreferenceKey::BetaFeatures => self.beta_features = parse(value)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn parse<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_str(value).map_err(|err| err.to_string())
+}
@@ -0,0 +1,22 @@
+use sqlx::FromRow;
+
+pub const DEFAULT_ROLE: &str = "User";
+pub const ADMIN_ROLE: &str = "Admin";
+
+#[derive(FromRow, Clone)]
+pub struct RoleEntity {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub is_system: bool,
+}
+
+pub struct CreateRoleEntity {
+    pub name: String,
+    pub parent_id: Option<i64>,
+}
+
+pub struct PermissionEntity {
+    pub name: String,
+    pub description: String,
+}
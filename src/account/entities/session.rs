@@ -5,13 +5,17 @@ use uuid::Uuid;
 
 use crate::core::{types::DbDateTime, AppConfig};
 
-#[derive(Type)]
+#[derive(Clone, Type)]
 pub enum RevocationReason {
     SessionExtended,
     LoggedOut,
     NewSession,
+    ImpersonationEnded,
+    PasswordReset,
+    Revoked,
 }
 
+#[derive(Clone)]
 pub struct SessionEntity {
     pub id: i64,
     pub user_id: i64,
@@ -21,6 +25,13 @@ pub struct SessionEntity {
     pub refresh_token_expiry: DbDateTime,
     pub revoked_at: Option<DbDateTime>,
     pub revocation_reason: Option<RevocationReason>,
+    pub impersonator_id: Option<i64>,
+    pub active_organization_id: Option<i64>,
+}
+
+pub struct Impersonation {
+    pub session_id: i64,
+    pub admin_id: i64,
 }
 
 #[derive(Getters)]
@@ -29,10 +40,12 @@ pub struct CreateSessionEntity {
     refresh_token: String,
     token_expiry: DbDateTime,
     refresh_token_expiry: DbDateTime,
+    impersonator_id: Option<i64>,
+    active_organization_id: Option<i64>,
 }
 
 impl CreateSessionEntity {
-    pub fn new(config: AppConfig) -> Self {
+    pub fn new(config: &AppConfig) -> Self {
         let now = Utc::now();
 
         Self {
@@ -40,6 +53,29 @@ impl CreateSessionEntity {
             refresh_token: Uuid::new_v4().to_string(),
             token_expiry: DbDateTime(now + config.session_duration),
             refresh_token_expiry: DbDateTime(now + config.session_refresh_duration),
+            impersonator_id: None,
+            active_organization_id: None,
+        }
+    }
+
+    // Impersonation sessions cannot be extended, so both tokens expire at the same time.
+    pub fn impersonation(config: &AppConfig, admin_id: i64) -> Self {
+        let expiry = Utc::now() + config.impersonation_duration;
+
+        Self {
+            token: Uuid::new_v4().to_string(),
+            refresh_token: Uuid::new_v4().to_string(),
+            token_expiry: DbDateTime(expiry),
+            refresh_token_expiry: DbDateTime(expiry),
+            impersonator_id: Some(admin_id),
+            active_organization_id: None,
+        }
+    }
+
+    pub fn with_active_organization(self, active_organization_id: Option<i64>) -> Self {
+        Self {
+            active_organization_id,
+            ..self
         }
     }
 }
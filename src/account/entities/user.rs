@@ -1,28 +1,40 @@
-use sqlx::{FromRow, Type};
+use sqlx::FromRow;
 
 use crate::core::types::DbDateTime;
 
-#[derive(PartialEq, Clone, Type)]
-pub enum UserRole {
-    User,
-    Admin,
-}
-
 #[derive(FromRow, Clone)]
 pub struct UserEntity {
     pub id: i64,
     pub email: String,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
     pub password: String,
-    pub role: UserRole,
+    pub role_id: i64,
+    pub role: String,
     pub login_attempts: i64,
     pub last_failed_login_attempt: Option<DbDateTime>,
 }
 
 pub struct CreateUserEntity {
     pub email: String,
+    pub username: Option<String>,
     pub password: String,
 }
 
+pub struct UpdateProfileEntity {
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+}
+
+pub struct UserFilter {
+    pub organization_id: Option<i64>,
+}
+
 pub struct FailedLoginAttempt {
     pub login_attempts: i64,
     pub last_failed_login_attempt: Option<DbDateTime>,
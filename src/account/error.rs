@@ -38,6 +38,66 @@ pub enum AccountError {
 
     #[error("Insufficient privilege to access this resource.")]
     InsufficientPrivilege,
+
+    #[error("User {0} cannot be impersonated.")]
+    CannotImpersonate(i64),
+
+    #[error("This action is not allowed while impersonating a user.")]
+    ForbiddenWhileImpersonating,
+
+    #[error("The current session is not an impersonation session.")]
+    NotImpersonating,
+
+    #[error("Missing permission {0} to access this resource.")]
+    MissingPermission(String),
+
+    #[error("Role {0} does not exist.")]
+    RoleDoesNotExist(i64),
+
+    #[error("Role {0} already exists.")]
+    RoleExistsByName(String),
+
+    #[error("Role {0} is a system role and cannot be modified.")]
+    SystemRoleImmutable(String),
+
+    #[error("Role {0} is still assigned to one or more users.")]
+    RoleInUse(String),
+
+    #[error("Permission {0} does not exist.")]
+    UnknownPermission(String),
+
+    #[error("A role cannot inherit from itself or one of its descendants.")]
+    RoleHierarchyCycle,
+
+    #[error("You cannot change your own role.")]
+    CannotChangeOwnRole,
+
+    #[error("Registration is by invitation only.")]
+    RegistrationClosed,
+
+    #[error("User {0} already exists.")]
+    UserExistsByUsername(String),
+
+    #[error("User {0} does not exist.")]
+    UserDoesNotExistByUsername(String),
+
+    #[error("Missing avatar file in request.")]
+    MissingAvatarFile,
+
+    #[error("Avatar must not be larger than {0} bytes.")]
+    AvatarTooLarge(usize),
+
+    #[error("Avatar must be a PNG, JPEG, GIF or WebP image.")]
+    UnsupportedAvatarFormat,
+
+    #[error("Avatar could not be read as an image.")]
+    InvalidAvatarImage(String),
+
+    #[error("Avatar does not exist.")]
+    AvatarDoesNotExist,
+
+    #[error("Role {0} is still the parent of one or more roles.")]
+    RoleHasChildren(String),
 }
 
 impl ErrorCode for AccountError {
@@ -53,6 +113,26 @@ impl ErrorCode for AccountError {
             AccountError::InvalidOrExpiredOtp => "ACC0008",
             AccountError::TokenPairMismatch => "ACC0009",
             AccountError::InsufficientPrivilege => "ACC0010",
+            AccountError::CannotImpersonate(_) => "ACC0011",
+            AccountError::ForbiddenWhileImpersonating => "ACC0012",
+            AccountError::NotImpersonating => "ACC0013",
+            AccountError::MissingPermission(_) => "ACC0014",
+            AccountError::RoleDoesNotExist(_) => "ACC0015",
+            AccountError::RoleExistsByName(_) => "ACC0016",
+            AccountError::SystemRoleImmutable(_) => "ACC0017",
+            AccountError::RoleInUse(_) => "ACC0018",
+            AccountError::UnknownPermission(_) => "ACC0019",
+            AccountError::RoleHierarchyCycle => "ACC0020",
+            AccountError::CannotChangeOwnRole => "ACC0021",
+            AccountError::RegistrationClosed => "ACC0022",
+            AccountError::UserExistsByUsername(_) => "ACC0023",
+            AccountError::UserDoesNotExistByUsername(_) => "ACC0024",
+            AccountError::MissingAvatarFile => "ACC0025",
+            AccountError::AvatarTooLarge(_) => "ACC0026",
+            AccountError::UnsupportedAvatarFormat => "ACC0027",
+            AccountError::InvalidAvatarImage(_) => "ACC0028",
+            AccountError::AvatarDoesNotExist => "ACC0029",
+            AccountError::RoleHasChildren(_) => "ACC0030",
         }
     }
 }
@@ -61,24 +141,58 @@ impl IntoApiErrorResponse for AccountError {
     fn into_app_error_response(&self) -> ApiErrorResponse {
         match &self {
             AccountError::UserExistsByEmail(_)
+            | AccountError::UserExistsByUsername(_)
             | AccountError::InvalidCredentials
             | AccountError::MaxLoginAttempts
-            | AccountError::InvalidOrExpiredOtp => ApiErrorResponse {
+            | AccountError::InvalidOrExpiredOtp
+            | AccountError::CannotImpersonate(_)
+            | AccountError::NotImpersonating
+            | AccountError::RoleExistsByName(_)
+            | AccountError::SystemRoleImmutable(_)
+            | AccountError::RoleInUse(_)
+            | AccountError::RoleHasChildren(_)
+            | AccountError::UnknownPermission(_)
+            | AccountError::RoleHierarchyCycle
+            | AccountError::CannotChangeOwnRole
+            | AccountError::MissingAvatarFile => ApiErrorResponse {
                 status_code: StatusCode::BAD_REQUEST,
                 code: self.code().into(),
                 message: self.to_string(),
                 debug_description: None,
                 validation_errors: vec![],
             },
-            AccountError::UserDoesNotExistByEmail(_) | AccountError::UserDoesNotExistById(_) => {
-                ApiErrorResponse {
-                    status_code: StatusCode::NOT_FOUND,
-                    code: self.code().into(),
-                    message: self.to_string(),
-                    debug_description: None,
-                    validation_errors: vec![],
-                }
-            }
+            AccountError::UserDoesNotExistByEmail(_)
+            | AccountError::UserDoesNotExistById(_)
+            | AccountError::UserDoesNotExistByUsername(_)
+            | AccountError::RoleDoesNotExist(_)
+            | AccountError::AvatarDoesNotExist => ApiErrorResponse {
+                status_code: StatusCode::NOT_FOUND,
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: None,
+                validation_errors: vec![],
+            },
+            AccountError::AvatarTooLarge(_) => ApiErrorResponse {
+                status_code: StatusCode::PAYLOAD_TOO_LARGE,
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: None,
+                validation_errors: vec![],
+            },
+            AccountError::UnsupportedAvatarFormat => ApiErrorResponse {
+                status_code: StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: None,
+                validation_errors: vec![],
+            },
+            AccountError::InvalidAvatarImage(error) => ApiErrorResponse {
+                status_code: StatusCode::BAD_REQUEST,
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: Some(error.clone()),
+                validation_errors: vec![],
+            },
             AccountError::MissingTokenInHeader => ApiErrorResponse {
                 status_code: StatusCode::UNAUTHORIZED,
                 code: self.code().into(),
@@ -103,7 +217,10 @@ impl IntoApiErrorResponse for AccountError {
                 debug_description: None,
                 validation_errors: vec![],
             },
-            AccountError::InsufficientPrivilege => ApiErrorResponse {
+            AccountError::InsufficientPrivilege
+            | AccountError::ForbiddenWhileImpersonating
+            | AccountError::MissingPermission(_)
+            | AccountError::RegistrationClosed => ApiErrorResponse {
                 status_code: StatusCode::FORBIDDEN,
                 code: self.code().into(),
                 message: self.to_string(),
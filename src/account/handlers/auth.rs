@@ -1,10 +1,12 @@
 use axum::{extract::State, response::NoContent};
+use serde_json::json;
+use sqlx::SqliteConnection;
 
 use crate::{
     account::{
         database::{session_repository, user_repository},
         entities::{
-            session::{CreateSessionEntity, RevocationReason},
+            session::{CreateSessionEntity, Impersonation, RevocationReason},
             user::{CreateUserEntity, FailedLoginAttempt},
         },
         error::AccountError,
@@ -12,14 +14,25 @@ use crate::{
             request::auth::{AuthenticateRequest, CreateUserRequest, ExtendSessionRequest},
             response::{auth::AuthenticatedResponse, user::UserResponse},
         },
-        utils::extractors::{CurrentUser, PossiblyExpiredSession},
+        utils::{
+            extractors::{CurrentUser, PossiblyExpiredSession},
+            normalize_email,
+        },
+    },
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
     },
     core::{
         error::{ApiError, ApiResult},
-        extractors::{JsonRequest, JsonResponse, ValidJsonRequest},
+        extractors::{ClientIp, JsonRequest, JsonResponse, UserAgent, ValidJsonRequest},
         types::DbDateTime,
         AppState,
     },
+    notification::{
+        dispatcher,
+        entities::notification::{CreateNotificationEntity, NotificationType},
+    },
 };
 
 #[axum::debug_handler]
@@ -27,7 +40,11 @@ pub async fn register(
     State(state): State<AppState>,
     ValidJsonRequest(request): ValidJsonRequest<CreateUserRequest>,
 ) -> ApiResult<JsonResponse<UserResponse>> {
-    let email = request.email;
+    if !state.config.registration_policy.open {
+        return Err(ApiError::AccountError(AccountError::RegistrationClosed));
+    }
+
+    let email = normalize_email(&state.config, &request.email);
     let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
 
     if user_repository::user_exists_by_email(&mut connection, &email).await? {
@@ -36,11 +53,26 @@ pub async fn register(
         )));
     }
 
+    let username = request.username.map(|username| username.trim().to_string());
+    if let Some(username) = &username {
+        if user_repository::user_exists_by_username(&mut connection, username, None).await? {
+            return Err(ApiError::AccountError(AccountError::UserExistsByUsername(
+                username.clone(),
+            )));
+        }
+    }
+
     let password = request.password;
-    let hash = crate::account::utils::hash_password(&password)?;
+    crate::core::validators::validate_password_not_breached(
+        &password,
+        &state.config.password_policy,
+    )
+    .await?;
+    let hash = crate::account::utils::hash_password(&state.config.argon2, &password)?;
 
     let user = CreateUserEntity {
         email,
+        username,
         password: hash,
     };
 
@@ -53,19 +85,67 @@ pub async fn register(
 #[axum::debug_handler]
 pub async fn login(
     State(state): State<AppState>,
+    ClientIp(ip_address): ClientIp,
+    UserAgent(user_agent): UserAgent,
     ValidJsonRequest(request): ValidJsonRequest<AuthenticateRequest>,
 ) -> ApiResult<JsonResponse<AuthenticatedResponse>> {
     let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
-    let email = request.email;
 
-    let Some(user) = user_repository::find_user_by_email(&mut connection, &email).await? else {
-        return Err(ApiError::AccountError(
-            AccountError::UserDoesNotExistByEmail(email),
-        ));
+    let (user, identifier) = if request.is_email() {
+        let email = normalize_email(&state.config, &request.identifier);
+        let user = user_repository::find_user_by_email(&mut connection, &email).await?;
+        (user, email)
+    } else {
+        let username = request.identifier.trim().to_string();
+        let user = user_repository::find_user_by_username(&mut connection, &username).await?;
+        (user, username)
+    };
+
+    let Some(user) = user else {
+        let reason = if request.is_email() {
+            "unknown_email"
+        } else {
+            "unknown_username"
+        };
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::LoginFailed, None, ip_address)
+                .with_details(json!({ "reason": reason, "identifier": identifier })),
+        )
+        .await?;
+
+        if state.config.anti_enumeration {
+            tracing::info!("Login attempted for non-existent user {identifier}");
+            crate::account::utils::dummy_verify_password(&state.config.argon2, &request.password);
+            return Err(ApiError::AccountError(AccountError::InvalidCredentials));
+        }
+
+        return Err(ApiError::AccountError(if request.is_email() {
+            AccountError::UserDoesNotExistByEmail(identifier)
+        } else {
+            AccountError::UserDoesNotExistByUsername(identifier)
+        }));
     };
 
-    validators::validate_max_login_attempts(&user)?;
-    let result = validators::validate_user_password(&request.password, &user.password).await;
+    if let Err(error) = validators::validate_max_login_attempts(&user) {
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::LoginFailed, Some(user.id), ip_address)
+                .with_details(json!({ "reason": "account_locked" })),
+        )
+        .await?;
+
+        if state.config.anti_enumeration {
+            tracing::info!("Login attempted for locked user {}", user.id);
+            crate::account::utils::dummy_verify_password(&state.config.argon2, &request.password);
+            return Err(ApiError::AccountError(AccountError::InvalidCredentials));
+        }
+
+        return Err(error);
+    }
+
+    let result =
+        validators::validate_user_password(&state.config, &request.password, &user.password).await;
 
     if let Err(error) = result {
         if let ApiError::AccountError(account_error) = &error {
@@ -74,6 +154,8 @@ pub async fn login(
                     login_attempts: user.login_attempts + 1,
                     last_failed_login_attempt: Some(DbDateTime::now()),
                 };
+                let is_locked =
+                    failed_login_attempt.login_attempts >= validators::MAX_LOGIN_ATTEMPTS;
 
                 user_repository::update_failed_login(
                     &mut connection,
@@ -81,15 +163,50 @@ pub async fn login(
                     failed_login_attempt,
                 )
                 .await?;
+
+                audit_repository::create_audit_event(
+                    &mut connection,
+                    CreateAuditEventEntity::new(
+                        AuditEventType::LoginFailed,
+                        Some(user.id),
+                        ip_address.clone(),
+                    )
+                    .with_details(json!({ "reason": "invalid_credentials" })),
+                )
+                .await?;
+
+                if is_locked {
+                    audit_repository::create_audit_event(
+                        &mut connection,
+                        CreateAuditEventEntity::new(
+                            AuditEventType::AccountLocked,
+                            Some(user.id),
+                            ip_address,
+                        ),
+                    )
+                    .await?;
+
+                    dispatcher::notify(
+                        &mut connection,
+                        &state.config,
+                        CreateNotificationEntity::new(NotificationType::AccountLocked, user.id),
+                    )
+                    .await?;
+                }
             }
         }
 
         return Err(error);
     }
 
-    let new_session = CreateSessionEntity::new(state.config);
+    let new_session = CreateSessionEntity::new(&state.config);
     let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
 
+    if result.is_ok_and(|password_match| password_match.is_outdated_match()) {
+        let hash = crate::account::utils::hash_password(&state.config.argon2, &request.password)?;
+        user_repository::update_password_by_user_id(&mut connection, user.id, &hash).await?;
+    }
+
     session_repository::revoke_session_for_user_id(
         &mut connection,
         user.id,
@@ -98,6 +215,24 @@ pub async fn login(
     .await?;
     let session = session_repository::create_session(&mut connection, user.id, new_session).await?;
 
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(
+            AuditEventType::LoginSucceeded,
+            Some(user.id),
+            ip_address.clone(),
+        ),
+    )
+    .await?;
+    dispatcher::notify_new_login(
+        &mut connection,
+        &state.config,
+        user.id,
+        ip_address,
+        user_agent,
+    )
+    .await?;
+
     connection.commit().await.map_err(ApiError::from)?;
 
     Ok(JsonResponse(AuthenticatedResponse {
@@ -113,13 +248,21 @@ pub async fn login(
 pub async fn extend_session(
     State(state): State<AppState>,
     PossiblyExpiredSession(session): PossiblyExpiredSession,
+    ClientIp(ip_address): ClientIp,
     JsonRequest(request): JsonRequest<ExtendSessionRequest>,
 ) -> ApiResult<JsonResponse<AuthenticatedResponse>> {
+    if session.impersonator_id.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
     if session.refresh_token != request.refresh_token {
         return Err(ApiError::AccountError(AccountError::TokenPairMismatch));
     }
 
-    let new_session = CreateSessionEntity::new(state.config);
+    let new_session = CreateSessionEntity::new(&state.config)
+        .with_active_organization(session.active_organization_id);
     let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
     let Some(user) = user_repository::find_user_by_id(&mut connection, session.user_id).await?
     else {
@@ -137,6 +280,16 @@ pub async fn extend_session(
     let session =
         session_repository::create_session(&mut connection, session.user_id, new_session).await?;
 
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(
+            AuditEventType::SessionExtended,
+            Some(session.user_id),
+            ip_address,
+        ),
+    )
+    .await?;
+
     connection.commit().await.map_err(ApiError::from)?;
 
     Ok(JsonResponse(AuthenticatedResponse {
@@ -151,9 +304,16 @@ pub async fn extend_session(
 #[axum::debug_handler]
 pub async fn logout(
     State(state): State<AppState>,
-    CurrentUser(user): CurrentUser,
+    CurrentUser(user, impersonation): CurrentUser,
+    ClientIp(ip_address): ClientIp,
 ) -> ApiResult<NoContent> {
-    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+    if let Some(impersonation) = impersonation {
+        end_impersonation_session(&mut connection, user.id, impersonation, ip_address).await?;
+        connection.commit().await.map_err(ApiError::from)?;
+        return Ok(NoContent);
+    }
 
     session_repository::revoke_session_for_user_id(
         &mut connection,
@@ -162,35 +322,88 @@ pub async fn logout(
     )
     .await?;
 
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(AuditEventType::LoggedOut, Some(user.id), ip_address),
+    )
+    .await?;
+
+    connection.commit().await.map_err(ApiError::from)?;
+
     Ok(NoContent)
 }
 
-mod validators {
-    use argon2::{Argon2, PasswordHash, PasswordVerifier};
+#[axum::debug_handler]
+pub async fn end_impersonation(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+    ClientIp(ip_address): ClientIp,
+) -> ApiResult<NoContent> {
+    let Some(impersonation) = impersonation else {
+        return Err(ApiError::AccountError(AccountError::NotImpersonating));
+    };
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+    end_impersonation_session(&mut connection, user.id, impersonation, ip_address).await?;
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(NoContent)
+}
+
+// Only revokes the impersonation session, the user's own sessions stay valid.
+async fn end_impersonation_session(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    impersonation: Impersonation,
+    ip_address: Option<String>,
+) -> ApiResult<()> {
+    session_repository::revoke_session_by_id(
+        connection,
+        impersonation.session_id,
+        RevocationReason::ImpersonationEnded,
+    )
+    .await?;
 
+    audit_repository::create_audit_event(
+        connection,
+        CreateAuditEventEntity::new(
+            AuditEventType::ImpersonationEnded,
+            Some(user_id),
+            ip_address,
+        )
+        .with_actor(impersonation.admin_id),
+    )
+    .await
+}
+
+mod validators {
     use crate::{
-        account::{entities::user::UserEntity, error::AccountError},
-        core::error::{ApiError, ApiResult},
+        account::{entities::user::UserEntity, error::AccountError, utils::PasswordMatch},
+        core::{
+            error::{ApiError, ApiResult},
+            AppConfig,
+        },
     };
 
-    pub async fn validate_user_password(password: &str, user_password: &str) -> ApiResult<()> {
-        let argon2 = Argon2::default();
-        let hash = PasswordHash::new(user_password).map_err(ApiError::from)?;
+    pub async fn validate_user_password(
+        config: &AppConfig,
+        password: &str,
+        user_password: &str,
+    ) -> ApiResult<PasswordMatch> {
+        let password_match =
+            crate::account::utils::verify_password(&config.argon2, password, user_password)?;
 
-        argon2
-            .verify_password(password.as_bytes(), &hash)
-            .map_err(|error| match error {
-                argon2::password_hash::Error::Password => {
-                    ApiError::AccountError(AccountError::InvalidCredentials)
-                }
-                _ => ApiError::Argon2PasswordHashError(error),
-            })?;
-
-        Ok(())
+        if password_match.is_mismatch() {
+            Err(ApiError::AccountError(AccountError::InvalidCredentials))
+        } else {
+            Ok(password_match)
+        }
     }
 
+    pub const MAX_LOGIN_ATTEMPTS: i64 = 3;
+
     pub fn validate_max_login_attempts(user: &UserEntity) -> ApiResult<()> {
-        if user.login_attempts >= 3 {
+        if user.login_attempts >= MAX_LOGIN_ATTEMPTS {
             Err(ApiError::AccountError(AccountError::MaxLoginAttempts))
         } else {
             Ok(())
@@ -0,0 +1,208 @@
+use axum::{
+    body::Body,
+    extract::{multipart::MultipartError, Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    account::{
+        database::user_repository,
+        error::AccountError,
+        models::response::user::UserResponse,
+        utils::{
+            avatar::{
+                avatar_key, avatar_url, parse_avatar_file, process_avatar, stored_avatar_name,
+                AVATAR_CONTENT_TYPE,
+            },
+            extractors::CurrentUser,
+        },
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        extractors::{JsonResponse, MultipartRequest},
+        storage::Storage,
+        AppState,
+    },
+};
+
+const AVATAR_FIELD: &str = "avatar";
+const AVATAR_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+#[axum::debug_handler]
+pub async fn upload_avatar(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+    MultipartRequest(mut multipart): MultipartRequest,
+) -> ApiResult<JsonResponse<UserResponse>> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let max_upload_size = state.config.avatar.max_upload_size;
+    // The body limit can also be hit while reading, which should be reported the same way.
+    let map_multipart_error = |err: MultipartError| {
+        if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::AccountError(AccountError::AvatarTooLarge(max_upload_size))
+        } else {
+            ApiError::from(err)
+        }
+    };
+
+    let mut bytes = None;
+    while let Some(mut field) = multipart.next_field().await.map_err(map_multipart_error)? {
+        if field.name() != Some(AVATAR_FIELD) {
+            continue;
+        }
+
+        let mut buffer = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(map_multipart_error)? {
+            if buffer.len() + chunk.len() > max_upload_size {
+                return Err(ApiError::AccountError(AccountError::AvatarTooLarge(
+                    max_upload_size,
+                )));
+            }
+            buffer.extend_from_slice(&chunk);
+        }
+        bytes = Some(buffer);
+        break;
+    }
+
+    let Some(bytes) = bytes.filter(|bytes| !bytes.is_empty()) else {
+        return Err(ApiError::AccountError(AccountError::MissingAvatarFile));
+    };
+
+    // Decoding and resizing are CPU bound, so keep them off the async runtime.
+    let config = state.config.avatar.clone();
+    let avatar = tokio::task::spawn_blocking(move || process_avatar(&config, &bytes))
+        .await
+        .map_err(|err| AccountError::InvalidAvatarImage(err.to_string()))??;
+
+    let storage = &state.storage;
+    storage
+        .put(&avatar_key(user.id, &avatar.name, false), avatar.image)
+        .await?;
+    storage
+        .put(&avatar_key(user.id, &avatar.name, true), avatar.thumbnail)
+        .await?;
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+    let url = avatar_url(&state.config, user.id, &avatar.name);
+    user_repository::update_avatar_url_by_user_id(&mut connection, user.id, Some(&url)).await?;
+    let updated_user = user_repository::find_user_by_id(&mut connection, user.id)
+        .await?
+        .ok_or(ApiError::AccountError(AccountError::UserDoesNotExistById(
+            user.id,
+        )))?;
+    connection.commit().await.map_err(ApiError::from)?;
+
+    if let Some(previous_url) = &user.avatar_url {
+        if previous_url != &url {
+            delete_stored_avatar(&state, user.id, previous_url).await;
+        }
+    }
+
+    Ok(JsonResponse(updated_user.into()))
+}
+
+#[axum::debug_handler]
+pub async fn delete_avatar(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+) -> ApiResult<JsonResponse<UserResponse>> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+    user_repository::update_avatar_url_by_user_id(&mut connection, user.id, None).await?;
+    let updated_user = user_repository::find_user_by_id(&mut connection, user.id)
+        .await?
+        .ok_or(ApiError::AccountError(AccountError::UserDoesNotExistById(
+            user.id,
+        )))?;
+    connection.commit().await.map_err(ApiError::from)?;
+
+    if let Some(previous_url) = &user.avatar_url {
+        delete_stored_avatar(&state, user.id, previous_url).await;
+    }
+
+    Ok(JsonResponse(updated_user.into()))
+}
+
+#[axum::debug_handler]
+pub async fn get_avatar(
+    State(state): State<AppState>,
+    Path((user_id, file)): Path<(i64, String)>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let Some((name, thumbnail)) = parse_avatar_file(&file) else {
+        return Err(ApiError::AccountError(AccountError::AvatarDoesNotExist));
+    };
+
+    // Checked first, so a conditional request cannot probe for avatars that do not exist.
+    let key = avatar_key(user_id, name, thumbnail);
+    if !state.storage.exists(&key).await? {
+        return Err(ApiError::AccountError(AccountError::AvatarDoesNotExist));
+    }
+
+    let etag = format!("\"{}\"", file.trim_end_matches(".png"));
+    let etag = HeaderValue::from_str(&etag).expect("avatar names are valid header values");
+    let cache_headers = [
+        (
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(AVATAR_CACHE_CONTROL),
+        ),
+        (header::ETAG, etag.clone()),
+    ];
+
+    if headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|value| value.trim() == etag || value.trim() == "*")
+    {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    let Some(bytes) = state.storage.get(&key).await? else {
+        return Err(ApiError::AccountError(AccountError::AvatarDoesNotExist));
+    };
+
+    Ok((
+        cache_headers,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(AVATAR_CONTENT_TYPE),
+            ),
+            (
+                header::X_CONTENT_TYPE_OPTIONS,
+                HeaderValue::from_static("nosniff"),
+            ),
+        ],
+        Body::from(bytes),
+    )
+        .into_response())
+}
+
+// Failing to clean up an old avatar leaves an orphaned file behind but must not fail the request.
+async fn delete_stored_avatar(state: &AppState, user_id: i64, url: &str) {
+    let Some(name) = stored_avatar_name(&state.config, user_id, url) else {
+        return;
+    };
+
+    for thumbnail in [false, true] {
+        let key = avatar_key(user_id, name, thumbnail);
+        if let Err(err) = state.storage.delete(&key).await {
+            tracing::warn!("Failed to delete avatar {key}: {err:?}");
+        }
+    }
+}
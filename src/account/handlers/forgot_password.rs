@@ -1,4 +1,6 @@
 use axum::{extract::State, response::NoContent};
+use serde_json::json;
+use uuid::Uuid;
 
 use crate::{
     account::{
@@ -11,32 +13,70 @@ use crate::{
             request::forgot_password::{RequestOtpRequest, ResetPasswordRequest, VerifyOtpRequest},
             response::forgot_password::VerifyOtpResponse,
         },
+        utils::{
+            dummy_verify_password, generate_otp, hash_password, hash_token, normalize_email,
+            normalize_otp, verify_password,
+        },
+    },
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
     },
     core::{
         error::{ApiError, ApiResult},
-        extractors::{JsonRequest, JsonResponse, ValidJsonRequest},
+        extractors::{ClientIp, JsonRequest, JsonResponse, ValidJsonRequest},
         AppState,
     },
+    notification::{
+        dispatcher,
+        entities::notification::{CreateNotificationEntity, NotificationType},
+    },
 };
 
 #[axum::debug_handler]
 pub async fn request_otp(
     State(state): State<AppState>,
+    ClientIp(ip_address): ClientIp,
     JsonRequest(request): JsonRequest<RequestOtpRequest>,
 ) -> ApiResult<()> {
-    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
-    let email = request.email;
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+    let email = normalize_email(&state.config, &request.email);
     let Some(user_id) = user_repository::find_user_by_email(&mut connection, &email)
         .await?
         .map(|u| u.id)
     else {
+        if state.config.anti_enumeration {
+            tracing::info!("OTP requested for non-existent user {email}");
+            let otp = generate_otp(state.config.otp_alphabet, state.config.otp_length);
+            hash_password(&state.config.argon2, &otp)?;
+            return Ok(());
+        }
+
         return Err(ApiError::AccountError(
             AccountError::UserDoesNotExistByEmail(email),
         ));
     };
 
-    let transaction = CreateForgotPasswordTransactionEntity::new(state.config, user_id);
+    let otp = generate_otp(state.config.otp_alphabet, state.config.otp_length);
+    let transaction = CreateForgotPasswordTransactionEntity::new(&state.config, user_id, &otp)?;
+    let expires_at = transaction.expires_at.0.to_rfc2822();
+
+    forgot_password_repository::invalidate_open_requests_for_user_id(&mut connection, user_id)
+        .await?;
     forgot_password_repository::request_otp(&mut connection, transaction).await?;
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(AuditEventType::OtpRequested, Some(user_id), ip_address),
+    )
+    .await?;
+    dispatcher::notify(
+        &mut connection,
+        &state.config,
+        CreateNotificationEntity::new(NotificationType::PasswordResetOtp, user_id)
+            .with_details(json!({ "otp": otp, "expiresAt": expires_at })),
+    )
+    .await?;
+    connection.commit().await.map_err(ApiError::from)?;
 
     Ok(())
 }
@@ -44,27 +84,80 @@ pub async fn request_otp(
 #[axum::debug_handler]
 pub async fn verify_otp(
     State(state): State<AppState>,
+    ClientIp(ip_address): ClientIp,
     JsonRequest(request): JsonRequest<VerifyOtpRequest>,
 ) -> ApiResult<JsonResponse<VerifyOtpResponse>> {
-    let email = request.email;
-    let token = request.otp;
+    let email = normalize_email(&state.config, &request.email);
+    let token = normalize_otp(&request.otp);
 
     let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
     let Some(user_id) = user_repository::find_user_by_email(&mut connection, &email)
         .await?
         .map(|u| u.id)
     else {
+        if state.config.anti_enumeration {
+            tracing::info!("OTP verification attempted for non-existent user {email}");
+            dummy_verify_password(&state.config.argon2, &token);
+            return Err(ApiError::AccountError(AccountError::InvalidOrExpiredOtp));
+        }
+
         return Err(ApiError::AccountError(
             AccountError::UserDoesNotExistByEmail(email),
         ));
     };
 
-    let transaction = VerifyForgotPasswordTransaction { user_id, token };
-    if let Some(verification_token) =
-        forgot_password_repository::verify_otp_request(&mut connection, transaction).await?
-    {
+    let max_attempts = state.config.otp_max_attempts;
+    let Some(transaction) = forgot_password_repository::find_open_request_by_user_id(
+        &mut connection,
+        user_id,
+        max_attempts,
+    )
+    .await?
+    else {
+        if state.config.anti_enumeration {
+            dummy_verify_password(&state.config.argon2, &token);
+        }
+
+        return Err(ApiError::AccountError(AccountError::InvalidOrExpiredOtp));
+    };
+
+    if verify_password(&state.config.argon2, &token, &transaction.token)?.is_mismatch() {
+        forgot_password_repository::record_failed_attempt(
+            &mut connection,
+            transaction.id,
+            max_attempts,
+        )
+        .await?;
+
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(
+                AuditEventType::OtpVerificationFailed,
+                Some(user_id),
+                ip_address,
+            )
+            .with_details(json!({ "attempts": transaction.attempts + 1 })),
+        )
+        .await?;
+
+        return Err(ApiError::AccountError(AccountError::InvalidOrExpiredOtp));
+    }
+
+    let reset_password_token = Uuid::new_v4().to_string();
+    let verification = VerifyForgotPasswordTransaction {
+        id: transaction.id,
+        reset_password_token: hash_token(&reset_password_token),
+    };
+
+    if forgot_password_repository::verify_otp_request(&mut connection, verification).await? {
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::OtpVerified, Some(user_id), ip_address),
+        )
+        .await?;
+
         Ok(JsonResponse(VerifyOtpResponse {
-            token: verification_token,
+            token: reset_password_token,
         }))
     } else {
         Err(ApiError::AccountError(AccountError::InvalidOrExpiredOtp))
@@ -74,18 +167,30 @@ pub async fn verify_otp(
 #[axum::debug_handler]
 pub async fn reset_password(
     State(state): State<AppState>,
+    ClientIp(ip_address): ClientIp,
     ValidJsonRequest(request): ValidJsonRequest<ResetPasswordRequest>,
 ) -> ApiResult<NoContent> {
-    let token = request.token;
+    let token = hash_token(&request.token);
     let password = request.password;
-    let hash = crate::account::utils::hash_password(&password)?;
 
     let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
 
     if let Some(user_id) =
         forgot_password_repository::consume_otp_request(&mut connection, &token).await?
     {
-        user_repository::update_password_by_user_id(&mut connection, user_id, &hash).await?;
+        crate::account::utils::update_password(&mut connection, &state.config, user_id, &password)
+            .await?;
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::PasswordReset, Some(user_id), ip_address),
+        )
+        .await?;
+        dispatcher::notify(
+            &mut connection,
+            &state.config,
+            CreateNotificationEntity::new(NotificationType::PasswordChanged, user_id),
+        )
+        .await?;
         connection.commit().await.map_err(ApiError::from)?;
         Ok(NoContent)
     } else {
@@ -1,3 +1,6 @@
 pub mod auth;
+pub mod avatar;
 pub mod forgot_password;
+pub mod preference;
+pub mod role;
 pub mod user;
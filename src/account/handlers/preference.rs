@@ -0,0 +1,72 @@
+use axum::extract::State;
+use sqlx::SqliteConnection;
+
+use crate::{
+    account::{
+        database::preference_repository,
+        error::AccountError,
+        models::{
+            request::preference::UpdatePreferencesRequest,
+            response::preference::PreferencesResponse,
+        },
+        utils::extractors::CurrentUser,
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        extractors::{JsonResponse, ValidJsonRequest},
+        AppConfig, AppState,
+    },
+};
+
+#[axum::debug_handler]
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    CurrentUser(user, _): CurrentUser,
+) -> ApiResult<JsonResponse<PreferencesResponse>> {
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+    find_preferences(&mut connection, &state.config, user.id)
+        .await
+        .map(JsonResponse)
+}
+
+#[axum::debug_handler]
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+    ValidJsonRequest(request): ValidJsonRequest<UpdatePreferencesRequest>,
+) -> ApiResult<JsonResponse<PreferencesResponse>> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+    for (key, value) in request.changes() {
+        match value {
+            Some(value) => {
+                preference_repository::upsert_preference(&mut connection, user.id, key, &value)
+                    .await?
+            }
+            None => preference_repository::delete_preference(&mut connection, user.id, key).await?,
+        }
+    }
+
+    let preferences = find_preferences(&mut connection, &state.config, user.id).await?;
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(JsonResponse(preferences))
+}
+
+async fn find_preferences(
+    connection: &mut SqliteConnection,
+    config: &AppConfig,
+    user_id: i64,
+) -> ApiResult<PreferencesResponse> {
+    preference_repository::find_preferences_by_user_id(connection, user_id)
+        .await
+        .map(|stored| config.preferences.defaults.clone().with_stored(stored))
+        .map(PreferencesResponse::from)
+}
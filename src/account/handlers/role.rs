@@ -0,0 +1,269 @@
+pub mod admin {
+    use std::collections::HashMap;
+
+    use axum::{
+        extract::{Path, State},
+        response::NoContent,
+    };
+    use serde_json::json;
+    use sqlx::SqliteConnection;
+
+    use crate::{
+        account::{
+            database::role_repository,
+            entities::role::{CreateRoleEntity, RoleEntity},
+            error::AccountError,
+            models::{
+                request::role::RoleRequest,
+                response::role::{PermissionResponse, RoleResponse},
+            },
+            utils::extractors::{CurrentUser, ManageRoles, RequirePermission},
+        },
+        audit::{
+            database::audit_repository,
+            entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+        },
+        core::{
+            error::{ApiError, ApiResult},
+            extractors::{ClientIp, JsonResponse, ValidJsonRequest},
+            AppState,
+        },
+    };
+
+    #[axum::debug_handler]
+    pub async fn get_roles(
+        State(state): State<AppState>,
+        RequirePermission(_): RequirePermission<ManageRoles>,
+    ) -> ApiResult<JsonResponse<Vec<RoleResponse>>> {
+        let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+        let roles = role_repository::find_roles(&mut connection).await?;
+
+        let mut permissions_by_role_id = HashMap::<i64, Vec<String>>::new();
+        for (role_id, permission) in
+            role_repository::find_all_direct_permissions(&mut connection).await?
+        {
+            permissions_by_role_id
+                .entry(role_id)
+                .or_default()
+                .push(permission);
+        }
+
+        Ok(JsonResponse(
+            roles
+                .into_iter()
+                .map(|role| {
+                    let permissions = permissions_by_role_id.remove(&role.id).unwrap_or_default();
+                    RoleResponse::new(role, permissions)
+                })
+                .collect(),
+        ))
+    }
+
+    #[axum::debug_handler]
+    pub async fn get_permissions(
+        State(state): State<AppState>,
+        RequirePermission(_): RequirePermission<ManageRoles>,
+    ) -> ApiResult<JsonResponse<Vec<PermissionResponse>>> {
+        let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+        role_repository::find_permissions(&mut connection)
+            .await
+            .map(|permissions| permissions.into_iter().map(Into::into).collect())
+            .map(JsonResponse)
+    }
+
+    #[axum::debug_handler]
+    pub async fn create_role(
+        State(state): State<AppState>,
+        CurrentUser(admin, _): CurrentUser,
+        RequirePermission(_): RequirePermission<ManageRoles>,
+        ClientIp(ip_address): ClientIp,
+        ValidJsonRequest(request): ValidJsonRequest<RoleRequest>,
+    ) -> ApiResult<JsonResponse<RoleResponse>> {
+        let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+        validators::validate_role(&mut connection, None, &request).await?;
+
+        let role = role_repository::create_role(
+            &mut connection,
+            CreateRoleEntity {
+                name: request.name.trim().into(),
+                parent_id: request.parent_id,
+            },
+        )
+        .await?;
+        role_repository::replace_role_permissions(&mut connection, role.id, &request.permissions)
+            .await?;
+
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::AdminAction, None, ip_address)
+                .with_actor(admin.id)
+                .with_details(json!({ "action": "create_role", "roleId": role.id })),
+        )
+        .await?;
+
+        let response = role_response(&mut connection, role).await?;
+        connection.commit().await.map_err(ApiError::from)?;
+
+        Ok(JsonResponse(response))
+    }
+
+    #[axum::debug_handler]
+    pub async fn update_role(
+        State(state): State<AppState>,
+        CurrentUser(admin, _): CurrentUser,
+        RequirePermission(_): RequirePermission<ManageRoles>,
+        ClientIp(ip_address): ClientIp,
+        Path(id): Path<i64>,
+        ValidJsonRequest(request): ValidJsonRequest<RoleRequest>,
+    ) -> ApiResult<JsonResponse<RoleResponse>> {
+        let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+        validators::find_mutable_role(&mut connection, id).await?;
+        validators::validate_role(&mut connection, Some(id), &request).await?;
+
+        let role = role_repository::update_role(
+            &mut connection,
+            id,
+            CreateRoleEntity {
+                name: request.name.trim().into(),
+                parent_id: request.parent_id,
+            },
+        )
+        .await?;
+        role_repository::replace_role_permissions(&mut connection, role.id, &request.permissions)
+            .await?;
+
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::AdminAction, None, ip_address)
+                .with_actor(admin.id)
+                .with_details(json!({ "action": "update_role", "roleId": role.id })),
+        )
+        .await?;
+
+        let response = role_response(&mut connection, role).await?;
+        connection.commit().await.map_err(ApiError::from)?;
+
+        Ok(JsonResponse(response))
+    }
+
+    #[axum::debug_handler]
+    pub async fn delete_role(
+        State(state): State<AppState>,
+        CurrentUser(admin, _): CurrentUser,
+        RequirePermission(_): RequirePermission<ManageRoles>,
+        ClientIp(ip_address): ClientIp,
+        Path(id): Path<i64>,
+    ) -> ApiResult<NoContent> {
+        let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+        let role = validators::find_mutable_role(&mut connection, id).await?;
+
+        if role_repository::count_users_by_role_id(&mut connection, role.id).await? > 0 {
+            return Err(ApiError::AccountError(AccountError::RoleInUse(role.name)));
+        }
+
+        // Children would silently lose the permissions they inherit from this role.
+        if role_repository::count_child_roles(&mut connection, role.id).await? > 0 {
+            return Err(ApiError::AccountError(AccountError::RoleHasChildren(
+                role.name,
+            )));
+        }
+
+        role_repository::delete_role(&mut connection, role.id).await?;
+
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::AdminAction, None, ip_address)
+                .with_actor(admin.id)
+                .with_details(
+                    json!({ "action": "delete_role", "roleId": role.id, "name": role.name }),
+                ),
+        )
+        .await?;
+
+        connection.commit().await.map_err(ApiError::from)?;
+
+        Ok(NoContent)
+    }
+
+    async fn role_response(
+        connection: &mut SqliteConnection,
+        role: RoleEntity,
+    ) -> ApiResult<RoleResponse> {
+        let permissions =
+            role_repository::find_direct_permissions_by_role_id(connection, role.id).await?;
+
+        Ok(RoleResponse::new(role, permissions))
+    }
+
+    mod validators {
+        use sqlx::SqliteConnection;
+
+        use crate::{
+            account::{
+                database::role_repository, entities::role::RoleEntity, error::AccountError,
+                models::request::role::RoleRequest,
+            },
+            core::error::{ApiError, ApiResult},
+        };
+
+        pub async fn find_mutable_role(
+            connection: &mut SqliteConnection,
+            id: i64,
+        ) -> ApiResult<RoleEntity> {
+            let Some(role) = role_repository::find_role_by_id(connection, id).await? else {
+                return Err(ApiError::AccountError(AccountError::RoleDoesNotExist(id)));
+            };
+
+            if role.is_system {
+                return Err(ApiError::AccountError(AccountError::SystemRoleImmutable(
+                    role.name,
+                )));
+            }
+
+            Ok(role)
+        }
+
+        pub async fn validate_role(
+            connection: &mut SqliteConnection,
+            id: Option<i64>,
+            request: &RoleRequest,
+        ) -> ApiResult<()> {
+            let name = request.name.trim();
+            if role_repository::role_exists_by_name(connection, name, id).await? {
+                return Err(ApiError::AccountError(AccountError::RoleExistsByName(
+                    name.into(),
+                )));
+            }
+
+            if let Some(parent_id) = request.parent_id {
+                if role_repository::find_role_by_id(connection, parent_id)
+                    .await?
+                    .is_none()
+                {
+                    return Err(ApiError::AccountError(AccountError::RoleDoesNotExist(
+                        parent_id,
+                    )));
+                }
+
+                let ancestry = role_repository::find_role_ancestry(connection, parent_id).await?;
+                if id.is_some_and(|id| ancestry.contains(&id)) {
+                    return Err(ApiError::AccountError(AccountError::RoleHierarchyCycle));
+                }
+            }
+
+            let permissions = role_repository::find_permissions(connection).await?;
+            if let Some(unknown) = request.permissions.iter().find(|requested| {
+                !permissions
+                    .iter()
+                    .any(|permission| &permission.name == *requested)
+            }) {
+                return Err(ApiError::AccountError(AccountError::UnknownPermission(
+                    unknown.clone(),
+                )));
+            }
+
+            Ok(())
+        }
+    }
+}
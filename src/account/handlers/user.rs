@@ -1,30 +1,128 @@
 use axum::extract::State;
 
 use crate::{
-    account::{models::response::user::UserResponse, utils::extractors::CurrentUser},
-    core::{extractors::JsonResponse, AppState},
+    account::{
+        database::user_repository,
+        entities::user::UpdateProfileEntity,
+        error::AccountError,
+        models::{
+            request::user::{UpdateProfileRequest, UpdateUsernameRequest},
+            response::user::UserResponse,
+        },
+        utils::extractors::CurrentUser,
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        extractors::{JsonResponse, ValidJsonRequest},
+        AppState,
+    },
 };
 
 pub async fn get_current_user(
     State(_): State<AppState>,
-    CurrentUser(user): CurrentUser,
+    CurrentUser(user, _): CurrentUser,
 ) -> JsonResponse<UserResponse> {
     JsonResponse(user.into())
 }
 
+#[axum::debug_handler]
+pub async fn update_current_user(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+    ValidJsonRequest(request): ValidJsonRequest<UpdateProfileRequest>,
+) -> ApiResult<JsonResponse<UserResponse>> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+    let profile = UpdateProfileEntity {
+        display_name: request
+            .display_name
+            .map(|display_name| display_name.map(|display_name| display_name.trim().to_string()))
+            .unwrap_or(user.display_name),
+        avatar_url: request.avatar_url.unwrap_or(user.avatar_url),
+        locale: request.locale.unwrap_or(user.locale),
+        timezone: request.timezone.unwrap_or(user.timezone),
+    };
+    user_repository::update_profile_by_user_id(&mut connection, user.id, profile).await?;
+
+    let user = user_repository::find_user_by_id(&mut connection, user.id)
+        .await?
+        .ok_or(ApiError::AccountError(AccountError::UserDoesNotExistById(
+            user.id,
+        )))?;
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(JsonResponse(user.into()))
+}
+
+#[axum::debug_handler]
+pub async fn update_username(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+    ValidJsonRequest(request): ValidJsonRequest<UpdateUsernameRequest>,
+) -> ApiResult<JsonResponse<UserResponse>> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+    let username = request.username.map(|username| username.trim().to_string());
+    if let Some(username) = &username {
+        if user_repository::user_exists_by_username(&mut connection, username, Some(user.id))
+            .await?
+        {
+            return Err(ApiError::AccountError(AccountError::UserExistsByUsername(
+                username.clone(),
+            )));
+        }
+    }
+
+    user_repository::update_username_by_user_id(&mut connection, user.id, username.as_deref())
+        .await?;
+    let user = user_repository::find_user_by_id(&mut connection, user.id)
+        .await?
+        .ok_or(ApiError::AccountError(AccountError::UserDoesNotExistById(
+            user.id,
+        )))?;
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(JsonResponse(user.into()))
+}
+
 pub mod admin {
-    use axum::extract::State;
+    use axum::extract::{Path, State};
     use axum_extra::extract::Query;
+    use serde_json::json;
 
     use crate::{
         account::{
-            database::user_repository,
-            models::response::user::UserResponse,
-            utils::extractors::{Admin, CurrentRole},
+            database::{role_repository, session_repository, user_repository},
+            entities::session::CreateSessionEntity,
+            error::AccountError,
+            models::{
+                request::{role::UpdateUserRoleRequest, user::UserFilterRequest},
+                response::{auth::ImpersonationResponse, user::UserResponse},
+            },
+            utils::extractors::{
+                CurrentUser, ImpersonateUsers, ManageRoles, Permission, ReadUsers,
+                RequirePermission,
+            },
+        },
+        audit::{
+            database::audit_repository,
+            entities::audit_event::{AuditEventType, CreateAuditEventEntity},
         },
         core::{
             error::{ApiError, ApiResult},
-            extractors::JsonResponse,
+            extractors::{ClientIp, JsonRequest, JsonResponse},
             models::{Page, PageRequest},
             AppState,
         },
@@ -33,13 +131,131 @@ pub mod admin {
     #[axum::debug_handler]
     pub async fn get_paginated_users(
         State(state): State<AppState>,
-        CurrentRole(_): CurrentRole<Admin>,
+        CurrentUser(admin, _): CurrentUser,
+        RequirePermission(_): RequirePermission<ReadUsers>,
+        ClientIp(ip_address): ClientIp,
+        Query(filter): Query<UserFilterRequest>,
         Query(request): Query<PageRequest>,
     ) -> ApiResult<JsonResponse<Page<UserResponse>>> {
         let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
-        user_repository::find_paginated_users(&mut connection, request)
+
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::AdminAction, None, ip_address)
+                .with_actor(admin.id)
+                .with_details(json!({ "action": "list_users" })),
+        )
+        .await?;
+        user_repository::find_paginated_users(&mut connection, filter.into(), request)
             .await
             .map(|users| users.map(|user| user.to_owned().into()))
             .map(JsonResponse)
     }
+
+    #[axum::debug_handler]
+    pub async fn impersonate_user(
+        State(state): State<AppState>,
+        CurrentUser(admin, _): CurrentUser,
+        RequirePermission(_): RequirePermission<ImpersonateUsers>,
+        ClientIp(ip_address): ClientIp,
+        Path(id): Path<i64>,
+    ) -> ApiResult<JsonResponse<ImpersonationResponse>> {
+        let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+        let Some(user) = user_repository::find_user_by_id(&mut connection, id).await? else {
+            return Err(ApiError::AccountError(AccountError::UserDoesNotExistById(
+                id,
+            )));
+        };
+
+        // Users who can impersonate others (i.e. staff) cannot be impersonated themselves, and
+        // impersonating must never grant the admin a permission they do not already hold.
+        let permissions =
+            role_repository::find_permissions_by_role_id(&mut connection, user.role_id).await?;
+        let admin_permissions =
+            role_repository::find_permissions_by_role_id(&mut connection, admin.role_id).await?;
+        if user.id == admin.id
+            || permissions.contains(&ImpersonateUsers::permission().to_string())
+            || permissions
+                .iter()
+                .any(|permission| !admin_permissions.contains(permission))
+        {
+            return Err(ApiError::AccountError(AccountError::CannotImpersonate(
+                user.id,
+            )));
+        }
+
+        let new_session = CreateSessionEntity::impersonation(&state.config, admin.id);
+        let session =
+            session_repository::create_session(&mut connection, user.id, new_session).await?;
+
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(
+                AuditEventType::ImpersonationStarted,
+                Some(user.id),
+                ip_address,
+            )
+            .with_actor(admin.id)
+            .with_details(json!({ "sessionId": session.id })),
+        )
+        .await?;
+
+        connection.commit().await.map_err(ApiError::from)?;
+
+        Ok(JsonResponse(ImpersonationResponse {
+            session_token: session.token,
+            session_token_expiry: session.token_expiry.into(),
+            impersonator_id: admin.id,
+            user: UserResponse::from(user),
+        }))
+    }
+
+    #[axum::debug_handler]
+    pub async fn update_user_role(
+        State(state): State<AppState>,
+        CurrentUser(admin, _): CurrentUser,
+        RequirePermission(_): RequirePermission<ManageRoles>,
+        ClientIp(ip_address): ClientIp,
+        Path(id): Path<i64>,
+        JsonRequest(request): JsonRequest<UpdateUserRoleRequest>,
+    ) -> ApiResult<JsonResponse<UserResponse>> {
+        let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+        let Some(user) = user_repository::find_user_by_id(&mut connection, id).await? else {
+            return Err(ApiError::AccountError(AccountError::UserDoesNotExistById(
+                id,
+            )));
+        };
+
+        if user.id == admin.id {
+            return Err(ApiError::AccountError(AccountError::CannotChangeOwnRole));
+        }
+
+        let Some(role) = role_repository::find_role_by_id(&mut connection, request.role_id).await?
+        else {
+            return Err(ApiError::AccountError(AccountError::RoleDoesNotExist(
+                request.role_id,
+            )));
+        };
+
+        user_repository::update_role_by_user_id(&mut connection, user.id, role.id).await?;
+
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::RoleChanged, Some(user.id), ip_address)
+                .with_actor(admin.id)
+                .with_details(json!({ "from": user.role, "to": role.name })),
+        )
+        .await?;
+
+        let user = user_repository::find_user_by_id(&mut connection, user.id)
+            .await?
+            .ok_or(ApiError::AccountError(AccountError::UserDoesNotExistById(
+                id,
+            )))?;
+        connection.commit().await.map_err(ApiError::from)?;
+
+        Ok(JsonResponse(user.into()))
+    }
 }
@@ -2,31 +2,36 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 
 use crate::core::{
-    constants::admin::ADMIN_EMAIL,
     validators::{self, Validatable},
+    AppConfig,
 };
 
 #[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AuthenticateRequest {
-    pub email: String,
+    /// Either the email or the username of the account.
+    #[serde(alias = "email")]
+    pub identifier: String,
     pub password: String,
 }
 
+impl AuthenticateRequest {
+    pub fn is_email(&self) -> bool {
+        self.identifier.contains('@')
+    }
+}
+
 impl Validatable for AuthenticateRequest {
     fn validated_properties() -> Vec<String> {
-        vec!["email".into(), "password".into()]
+        vec!["identifier".into(), "password".into()]
     }
 
-    fn validate_property(&self, property: &str) -> Option<Vec<String>> {
+    fn validate_property(&self, property: &str, config: &AppConfig) -> Option<Vec<String>> {
         match property {
-            "email" => validators::is_email_valid(&self.email),
+            "identifier" if self.is_email() => validators::is_email_valid(&self.identifier),
+            "identifier" => validators::is_username_valid(self.identifier.trim()),
             "password" => {
-                if self.email == ADMIN_EMAIL {
-                    None
-                } else {
-                    validators::is_password_valid(&self.password)
-                }
+                validators::is_login_password_valid(&self.password, &config.password_policy)
             }
             _ => None,
         }
@@ -37,18 +42,29 @@ impl Validatable for AuthenticateRequest {
 #[serde(rename_all = "camelCase")]
 pub struct CreateUserRequest {
     pub email: String,
+    pub username: Option<String>,
     pub password: String,
 }
 
 impl Validatable for CreateUserRequest {
     fn validated_properties() -> Vec<String> {
-        vec!["email".into(), "password".into()]
+        vec!["email".into(), "username".into(), "password".into()]
     }
 
-    fn validate_property(&self, property: &str) -> Option<Vec<String>> {
+    fn validate_property(&self, property: &str, config: &AppConfig) -> Option<Vec<String>> {
         match property {
-            "email" => validators::is_email_valid(&self.email),
-            "password" => validators::is_password_valid(&self.password),
+            "email" => validators::is_email_valid(&self.email).or_else(|| {
+                validators::is_email_domain_allowed(&self.email, &config.registration_policy)
+            }),
+            "username" => self
+                .username
+                .as_deref()
+                .and_then(validators::is_username_valid),
+            "password" => validators::is_password_valid(
+                &self.password,
+                Some(&self.email),
+                &config.password_policy,
+            ),
             _ => None,
         }
     }
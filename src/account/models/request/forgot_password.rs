@@ -1,7 +1,10 @@
 use schemars::JsonSchema;
 use serde::Deserialize;
 
-use crate::core::validators::{self, Validatable};
+use crate::core::{
+    validators::{self, Validatable},
+    AppConfig,
+};
 
 #[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -20,8 +23,6 @@ pub struct VerifyOtpRequest {
 #[serde(rename_all = "camelCase")]
 pub struct ResetPasswordRequest {
     pub token: String,
-
-    #[validate(custom = validators::is_password_valid)]
     pub password: String,
 }
 
@@ -30,9 +31,11 @@ impl Validatable for ResetPasswordRequest {
         vec!["password".into()]
     }
 
-    fn validate_property(&self, property: &str) -> Option<Vec<String>> {
+    fn validate_property(&self, property: &str, config: &AppConfig) -> Option<Vec<String>> {
         match property {
-            "password" => validators::is_password_valid(&self.password),
+            "password" => {
+                validators::is_password_valid(&self.password, None, &config.password_policy)
+            }
             _ => None,
         }
     }
@@ -0,0 +1,103 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    account::entities::preference::{EmailDigest, PreferenceKey, Theme},
+    core::{utils::deserialize_some, validators::Validatable, AppConfig},
+};
+
+const MAX_BETA_FEATURES: usize = 32;
+
+// JSON merge patch (RFC 7396): omitted preferences are left unchanged and `null` resets a
+// preference to its default.
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UpdatePreferencesRequest {
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub theme: Option<Option<Theme>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub reduced_motion: Option<Option<bool>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub email_digest: Option<Option<EmailDigest>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub desktop_notifications: Option<Option<bool>>,
+    /// Beta features to opt into, see the features enabled on the server.
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub beta_features: Option<Option<Vec<String>>>,
+}
+
+impl UpdatePreferencesRequest {
+    // Preferences to store as JSON, or to reset when `None`.
+    pub fn changes(&self) -> Vec<(PreferenceKey, Option<String>)> {
+        let beta_features = self.beta_features.as_ref().map(|features| {
+            features.as_ref().map(|features| {
+                let mut features = features.clone();
+                features.sort();
+                features.dedup();
+                features
+            })
+        });
+
+        [
+            change(PreferenceKey::Theme, &self.theme),
+            change(PreferenceKey::ReducedMotion, &self.reduced_motion),
+            change(PreferenceKey::EmailDigest, &self.email_digest),
+            change(
+                PreferenceKey::DesktopNotifications,
+                &self.desktop_notifications,
+            ),
+            change(PreferenceKey::BetaFeatures, &beta_features),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+fn change<T: Serialize>(
+    key: PreferenceKey,
+    value: &Option<Option<T>>,
+) -> Option<(PreferenceKey, Option<String>)> {
+    value.as_ref().map(|value| {
+        let value = value
+            .as_ref()
+            .map(|value| serde_json::to_string(value).expect("preferences serialize to JSON"));
+
+        (key, value)
+    })
+}
+
+impl Validatable for UpdatePreferencesRequest {
+    fn validated_properties() -> Vec<String> {
+        vec!["betaFeatures".into()]
+    }
+
+    fn validate_property(&self, property: &str, config: &AppConfig) -> Option<Vec<String>> {
+        match property {
+            "betaFeatures" => self
+                .beta_features
+                .as_ref()
+                .and_then(Option::as_ref)
+                .and_then(|features| {
+                    let mut errors = features
+                        .iter()
+                        .filter(|feature| !config.preferences.beta_features.contains(feature))
+                        .map(|feature| format!("{feature} is not an available beta feature."))
+                        .collect::<Vec<String>>();
+
+                    if features.len() > MAX_BETA_FEATURES {
+                        errors.push(format!(
+                            "At most {MAX_BETA_FEATURES} beta features can be enabled."
+                        ));
+                    }
+
+                    if errors.is_empty() {
+                        None
+                    } else {
+                        Some(errors)
+                    }
+                }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::core::{validators::Validatable, AppConfig};
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleRequest {
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub permissions: Vec<String>,
+}
+
+impl Validatable for RoleRequest {
+    fn validated_properties() -> Vec<String> {
+        vec!["name".into()]
+    }
+
+    fn validate_property(&self, property: &str, _: &AppConfig) -> Option<Vec<String>> {
+        match property {
+            "name" => {
+                let name = self.name.trim();
+                if name.is_empty() || name.chars().count() > 64 {
+                    Some(vec![
+                        "Role name must be between 1 and 64 characters long.".into()
+                    ])
+                } else if !name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-'))
+                {
+                    Some(vec![
+                        "Role name may only contain letters, numbers, spaces, _ and -.".into(),
+                    ])
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserRoleRequest {
+    pub role_id: i64,
+}
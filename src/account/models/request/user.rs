@@ -0,0 +1,109 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::{
+    account::entities::user::UserFilter,
+    core::{
+        utils::deserialize_some,
+        validators::{self, Validatable},
+        AppConfig,
+    },
+};
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserFilterRequest {
+    pub organization_id: Option<i64>,
+}
+
+impl From<UserFilterRequest> for UserFilter {
+    fn from(value: UserFilterRequest) -> Self {
+        Self {
+            organization_id: value.organization_id,
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUsernameRequest {
+    /// Removes the username when `null`.
+    pub username: Option<String>,
+}
+
+impl Validatable for UpdateUsernameRequest {
+    fn validated_properties() -> Vec<String> {
+        vec!["username".into()]
+    }
+
+    fn validate_property(&self, property: &str, _: &AppConfig) -> Option<Vec<String>> {
+        match property {
+            "username" => self
+                .username
+                .as_deref()
+                .map(str::trim)
+                .and_then(validators::is_username_valid),
+            _ => None,
+        }
+    }
+}
+
+// JSON merge patch (RFC 7396): omitted fields are left unchanged and `null` clears a field.
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProfileRequest {
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub display_name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub avatar_url: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub locale: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub timezone: Option<Option<String>>,
+}
+
+impl Validatable for UpdateProfileRequest {
+    fn validated_properties() -> Vec<String> {
+        vec![
+            "displayName".into(),
+            "avatarUrl".into(),
+            "locale".into(),
+            "timezone".into(),
+        ]
+    }
+
+    fn validate_property(&self, property: &str, _: &AppConfig) -> Option<Vec<String>> {
+        match property {
+            "displayName" => self
+                .display_name
+                .as_ref()
+                .and_then(Option::as_deref)
+                .and_then(|display_name| {
+                    let length = display_name.trim().chars().count();
+                    if length == 0 || length > 64 {
+                        Some(vec![
+                            "Display name must be between 1 and 64 characters long.".into(),
+                        ])
+                    } else {
+                        None
+                    }
+                }),
+            "avatarUrl" => self
+                .avatar_url
+                .as_ref()
+                .and_then(Option::as_deref)
+                .and_then(validators::is_http_url_valid),
+            "locale" => self
+                .locale
+                .as_ref()
+                .and_then(Option::as_deref)
+                .and_then(validators::is_locale_valid),
+            "timezone" => self
+                .timezone
+                .as_ref()
+                .and_then(Option::as_deref)
+                .and_then(validators::is_timezone_valid),
+            _ => None,
+        }
+    }
+}
@@ -13,3 +13,12 @@ pub struct AuthenticatedResponse {
     pub refresh_token_expiry: DateTime<Utc>,
     pub user: UserResponse,
 }
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpersonationResponse {
+    pub session_token: String,
+    pub session_token_expiry: DateTime<Utc>,
+    pub impersonator_id: i64,
+    pub user: UserResponse,
+}
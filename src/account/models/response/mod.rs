@@ -1,3 +1,5 @@
 pub mod auth;
 pub mod forgot_password;
+pub mod preference;
+pub mod role;
 pub mod user;
@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::account::entities::preference::{EmailDigest, PreferencesEntity, Theme};
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferencesResponse {
+    pub theme: Theme,
+    pub reduced_motion: bool,
+    pub email_digest: EmailDigest,
+    pub desktop_notifications: bool,
+    pub beta_features: Vec<String>,
+}
+
+impl From<PreferencesEntity> for PreferencesResponse {
+    fn from(value: PreferencesEntity) -> Self {
+        Self {
+            theme: value.theme,
+            reduced_motion: value.reduced_motion,
+            email_digest: value.email_digest,
+            desktop_notifications: value.desktop_notifications,
+            beta_features: value.beta_features,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::account::entities::role::{PermissionEntity, RoleEntity};
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleResponse {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub system: bool,
+    pub permissions: Vec<String>,
+}
+
+impl RoleResponse {
+    pub fn new(role: RoleEntity, permissions: Vec<String>) -> Self {
+        Self {
+            id: role.id,
+            name: role.name,
+            parent_id: role.parent_id,
+            system: role.is_system,
+            permissions,
+        }
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionResponse {
+    pub name: String,
+    pub description: String,
+}
+
+impl From<PermissionEntity> for PermissionResponse {
+    fn from(value: PermissionEntity) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+        }
+    }
+}
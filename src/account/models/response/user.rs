@@ -8,6 +8,12 @@ use crate::account::entities::user::UserEntity;
 pub struct UserResponse {
     pub id: i64,
     pub email: String,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub role: String,
 }
 
 impl From<UserEntity> for UserResponse {
@@ -15,6 +21,12 @@ impl From<UserEntity> for UserResponse {
         Self {
             id: value.id,
             email: value.email,
+            username: value.username,
+            display_name: value.display_name,
+            avatar_url: value.avatar_url,
+            locale: value.locale,
+            timezone: value.timezone,
+            role: value.role,
         }
     }
 }
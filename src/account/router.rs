@@ -1,18 +1,71 @@
+use std::time::Duration;
+
 use aide::axum::{
-    routing::{get, post},
+    routing::{get, post, put},
     ApiRouter,
 };
+use axum::{extract::DefaultBodyLimit, middleware::from_fn_with_state};
 
 use crate::core::{
     constants::openapi::{
         tags::{admin, AUTH_TAG, FORGOT_PASSWORD_TAG, USER_TAG},
         DEFAULT_SECURITY_SCHEME,
     },
+    rate_limit::{rate_limit, RateLimitKey, RateLimitPolicy, RateLimiter},
     AppState,
 };
 
 use super::handlers;
 
+const REGISTER_BY_IP: RateLimitPolicy =
+    RateLimitPolicy::new("register:ip", RateLimitKey::Ip, 5, Duration::from_secs(60));
+const LOGIN_BY_IP: RateLimitPolicy =
+    RateLimitPolicy::new("login:ip", RateLimitKey::Ip, 20, Duration::from_secs(30));
+const LOGIN_BY_ACCOUNT: RateLimitPolicy = RateLimitPolicy::new(
+    "login:account",
+    RateLimitKey::Account,
+    5,
+    Duration::from_secs(60),
+);
+const EXTEND_SESSION_BY_USER: RateLimitPolicy = RateLimitPolicy::new(
+    "extend:user",
+    RateLimitKey::UserId,
+    10,
+    Duration::from_secs(60),
+);
+const REQUEST_OTP_BY_IP: RateLimitPolicy = RateLimitPolicy::new(
+    "request-otp:ip",
+    RateLimitKey::Ip,
+    5,
+    Duration::from_secs(60),
+);
+const REQUEST_OTP_BY_ACCOUNT: RateLimitPolicy = RateLimitPolicy::new(
+    "request-otp:account",
+    RateLimitKey::Account,
+    3,
+    Duration::from_secs(300),
+);
+const VERIFY_OTP_BY_IP: RateLimitPolicy = RateLimitPolicy::new(
+    "verify-otp:ip",
+    RateLimitKey::Ip,
+    10,
+    Duration::from_secs(60),
+);
+const VERIFY_OTP_BY_ACCOUNT: RateLimitPolicy = RateLimitPolicy::new(
+    "verify-otp:account",
+    RateLimitKey::Account,
+    5,
+    Duration::from_secs(300),
+);
+const UPLOAD_AVATAR_BY_USER: RateLimitPolicy = RateLimitPolicy::new(
+    "upload-avatar:user",
+    RateLimitKey::UserId,
+    10,
+    Duration::from_secs(600),
+);
+const RESET_PASSWORD_BY_IP: RateLimitPolicy =
+    RateLimitPolicy::new("reset:ip", RateLimitKey::Ip, 5, Duration::from_secs(60));
+
 pub fn router(state: AppState) -> ApiRouter {
     ApiRouter::new().nest(
         "/account",
@@ -20,56 +73,187 @@ pub fn router(state: AppState) -> ApiRouter {
             .nest(
                 "/auth",
                 ApiRouter::new()
-                    .api_route_with("/register", post(handlers::auth::register), |op| {
-                        op.tag(AUTH_TAG)
-                    })
-                    .api_route_with("/login", post(handlers::auth::login), |op| op.tag(AUTH_TAG))
-                    .api_route_with("/extend", post(handlers::auth::extend_session), |op| {
-                        op.tag(AUTH_TAG)
-                            .security_requirement(DEFAULT_SECURITY_SCHEME)
-                    })
+                    .api_route_with(
+                        "/register",
+                        post(handlers::auth::register).route_layer(from_fn_with_state(
+                            RateLimiter::new(&state, &[REGISTER_BY_IP]),
+                            rate_limit,
+                        )),
+                        |op| op.tag(AUTH_TAG),
+                    )
+                    .api_route_with(
+                        "/login",
+                        post(handlers::auth::login).route_layer(from_fn_with_state(
+                            RateLimiter::new(&state, &[LOGIN_BY_IP, LOGIN_BY_ACCOUNT]),
+                            rate_limit,
+                        )),
+                        |op| op.tag(AUTH_TAG),
+                    )
+                    .api_route_with(
+                        "/extend",
+                        post(handlers::auth::extend_session).route_layer(from_fn_with_state(
+                            RateLimiter::new(&state, &[EXTEND_SESSION_BY_USER]),
+                            rate_limit,
+                        )),
+                        |op| {
+                            op.tag(AUTH_TAG)
+                                .security_requirement(DEFAULT_SECURITY_SCHEME)
+                        },
+                    )
                     .api_route_with("/logout", post(handlers::auth::logout), |op| {
                         op.tag(AUTH_TAG)
                             .security_requirement(DEFAULT_SECURITY_SCHEME)
-                    }),
+                    })
+                    .api_route_with(
+                        "/impersonation/end",
+                        post(handlers::auth::end_impersonation),
+                        |op| {
+                            op.tag(AUTH_TAG)
+                                .security_requirement(DEFAULT_SECURITY_SCHEME)
+                        },
+                    ),
             )
             .nest(
                 "/forgot-password",
                 ApiRouter::new()
                     .api_route_with(
                         "/request-otp",
-                        post(handlers::forgot_password::request_otp),
+                        post(handlers::forgot_password::request_otp).route_layer(
+                            from_fn_with_state(
+                                RateLimiter::new(
+                                    &state,
+                                    &[REQUEST_OTP_BY_IP, REQUEST_OTP_BY_ACCOUNT],
+                                ),
+                                rate_limit,
+                            ),
+                        ),
                         |op| op.tag(FORGOT_PASSWORD_TAG),
                     )
                     .api_route_with(
                         "/verify-otp",
-                        post(handlers::forgot_password::verify_otp),
+                        post(handlers::forgot_password::verify_otp).route_layer(
+                            from_fn_with_state(
+                                RateLimiter::new(
+                                    &state,
+                                    &[VERIFY_OTP_BY_IP, VERIFY_OTP_BY_ACCOUNT],
+                                ),
+                                rate_limit,
+                            ),
+                        ),
                         |op| op.tag(FORGOT_PASSWORD_TAG),
                     )
                     .api_route_with(
                         "/reset",
-                        post(handlers::forgot_password::reset_password),
+                        post(handlers::forgot_password::reset_password).route_layer(
+                            from_fn_with_state(
+                                RateLimiter::new(&state, &[RESET_PASSWORD_BY_IP]),
+                                rate_limit,
+                            ),
+                        ),
                         |op| op.tag(FORGOT_PASSWORD_TAG),
                     ),
             )
             .nest(
                 "/users",
-                ApiRouter::new().api_route_with(
-                    "/me",
-                    get(handlers::user::get_current_user),
-                    |op| {
+                ApiRouter::new()
+                    .api_route_with(
+                        "/me",
+                        get(handlers::user::get_current_user)
+                            .patch(handlers::user::update_current_user),
+                        |op| {
+                            op.tag(USER_TAG)
+                                .security_requirement(DEFAULT_SECURITY_SCHEME)
+                        },
+                    )
+                    .api_route_with("/me/username", put(handlers::user::update_username), |op| {
                         op.tag(USER_TAG)
                             .security_requirement(DEFAULT_SECURITY_SCHEME)
-                    },
-                ),
+                    })
+                    .api_route_with(
+                        "/me/preferences",
+                        get(handlers::preference::get_preferences)
+                            .patch(handlers::preference::update_preferences),
+                        |op| {
+                            op.tag(USER_TAG)
+                                .security_requirement(DEFAULT_SECURITY_SCHEME)
+                        },
+                    )
+                    .api_route_with(
+                        "/me/avatar",
+                        put(handlers::avatar::upload_avatar)
+                            .route_layer(from_fn_with_state(
+                                RateLimiter::new(&state, &[UPLOAD_AVATAR_BY_USER]),
+                                rate_limit,
+                            ))
+                            // Leaves room for the multipart framing around the file itself.
+                            .layer(DefaultBodyLimit::max(
+                                state.config.avatar.max_upload_size + 64 * 1024,
+                            ))
+                            .delete(handlers::avatar::delete_avatar),
+                        |op| {
+                            op.tag(USER_TAG)
+                                .security_requirement(DEFAULT_SECURITY_SCHEME)
+                        },
+                    )
+                    .api_route_with(
+                        "/{id}/avatars/{file}",
+                        get(handlers::avatar::get_avatar),
+                        |op| op.tag(USER_TAG),
+                    ),
             )
             .nest(
                 "/admin/users",
+                ApiRouter::new()
+                    .api_route_with("/", get(handlers::user::admin::get_paginated_users), |op| {
+                        op.tag(admin::USER_TAG)
+                            .security_requirement(DEFAULT_SECURITY_SCHEME)
+                    })
+                    .api_route_with(
+                        "/{id}/impersonate",
+                        post(handlers::user::admin::impersonate_user),
+                        |op| {
+                            op.tag(admin::USER_TAG)
+                                .security_requirement(DEFAULT_SECURITY_SCHEME)
+                        },
+                    )
+                    .api_route_with(
+                        "/{id}/role",
+                        put(handlers::user::admin::update_user_role),
+                        |op| {
+                            op.tag(admin::USER_TAG)
+                                .security_requirement(DEFAULT_SECURITY_SCHEME)
+                        },
+                    ),
+            )
+            .nest(
+                "/admin/roles",
+                ApiRouter::new()
+                    .api_route_with(
+                        "/",
+                        get(handlers::role::admin::get_roles)
+                            .post(handlers::role::admin::create_role),
+                        |op| {
+                            op.tag(admin::ROLE_TAG)
+                                .security_requirement(DEFAULT_SECURITY_SCHEME)
+                        },
+                    )
+                    .api_route_with(
+                        "/{id}",
+                        put(handlers::role::admin::update_role)
+                            .delete(handlers::role::admin::delete_role),
+                        |op| {
+                            op.tag(admin::ROLE_TAG)
+                                .security_requirement(DEFAULT_SECURITY_SCHEME)
+                        },
+                    ),
+            )
+            .nest(
+                "/admin/permissions",
                 ApiRouter::new().api_route_with(
                     "/",
-                    get(handlers::user::admin::get_paginated_users),
+                    get(handlers::role::admin::get_permissions),
                     |op| {
-                        op.tag(admin::USER_TAG)
+                        op.tag(admin::ROLE_TAG)
                             .security_requirement(DEFAULT_SECURITY_SCHEME)
                     },
                 ),
@@ -0,0 +1,151 @@
+use std::io::Cursor;
+
+use axum::body::Bytes;
+use image::{imageops::FilterType, DynamicImage, ImageFormat, ImageReader, Limits};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    account::error::AccountError,
+    core::{AppConfig, AvatarConfig},
+};
+
+pub const AVATAR_CONTENT_TYPE: &str = "image/png";
+const THUMBNAIL_SUFFIX: &str = "-thumb";
+
+pub struct ProcessedAvatar {
+    pub name: String,
+    pub image: Bytes,
+    pub thumbnail: Bytes,
+}
+
+// The format is sniffed from the file itself, the declared content type is never trusted. Every
+// avatar is re-encoded, which also strips metadata such as EXIF location data.
+pub fn process_avatar(
+    config: &AvatarConfig,
+    bytes: &[u8],
+) -> Result<ProcessedAvatar, AccountError> {
+    let format = image::guess_format(bytes).map_err(|_| AccountError::UnsupportedAvatarFormat)?;
+    if !matches!(
+        format,
+        ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP
+    ) {
+        return Err(AccountError::UnsupportedAvatarFormat);
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(config.max_dimension);
+    limits.max_image_height = Some(config.max_dimension);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+    let image = reader
+        .decode()
+        .map_err(|err| AccountError::InvalidAvatarImage(err.to_string()))?;
+
+    let image = image.resize_to_fill(config.size, config.size, FilterType::Lanczos3);
+    let thumbnail = image.resize_to_fill(
+        config.thumbnail_size,
+        config.thumbnail_size,
+        FilterType::Lanczos3,
+    );
+
+    let image = encode_png(image)?;
+    let thumbnail = encode_png(thumbnail)?;
+    let name = Sha256::digest(&image)
+        .iter()
+        .take(16)
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+
+    Ok(ProcessedAvatar {
+        name,
+        image,
+        thumbnail,
+    })
+}
+
+fn encode_png(image: DynamicImage) -> Result<Bytes, AccountError> {
+    let mut bytes = Vec::new();
+    DynamicImage::from(image.to_rgba8())
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|err| AccountError::InvalidAvatarImage(err.to_string()))?;
+
+    Ok(bytes.into())
+}
+
+pub fn avatar_key(user_id: i64, name: &str, thumbnail: bool) -> String {
+    let suffix = if thumbnail { THUMBNAIL_SUFFIX } else { "" };
+    format!("avatars/{user_id}/{name}{suffix}.png")
+}
+
+// Avatars are content addressed, so their URLs never change and can be cached indefinitely.
+pub fn avatar_url(config: &AppConfig, user_id: i64, name: &str) -> String {
+    format!("{}{name}.png", avatar_url_prefix(config, user_id))
+}
+
+// Returns the name of the avatar stored for the user, if the URL points to one.
+pub fn stored_avatar_name<'a>(config: &AppConfig, user_id: i64, url: &'a str) -> Option<&'a str> {
+    let name = url
+        .strip_prefix(&avatar_url_prefix(config, user_id))?
+        .strip_suffix(".png")?;
+
+    is_avatar_name(name).then_some(name)
+}
+
+fn avatar_url_prefix(config: &AppConfig, user_id: i64) -> String {
+    format!("{}/account/users/{user_id}/avatars/", config.public_url)
+}
+
+// Splits a served file name such as `{name}-thumb.png` into the avatar name and whether the
+// thumbnail was requested.
+pub fn parse_avatar_file(file: &str) -> Option<(&str, bool)> {
+    let file = file.strip_suffix(".png")?;
+    let (name, thumbnail) = match file.strip_suffix(THUMBNAIL_SUFFIX) {
+        Some(name) => (name, true),
+        None => (file, false),
+    };
+
+    is_avatar_name(name).then_some((name, thumbnail))
+}
+
+fn is_avatar_name(name: &str) -> bool {
+    name.len() == 32
+        && name
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAME: &str = "0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn parse_avatar_file_splits_thumbnails() {
+        assert_eq!(
+            parse_avatar_file(&format!("{NAME}.png")),
+            Some((NAME, false))
+        );
+        assert_eq!(
+            parse_avatar_file(&format!("{NAME}-thumb.png")),
+            Some((NAME, true))
+        );
+    }
+
+    #[test]
+    fn parse_avatar_file_rejects_other_files() {
+        for file in [
+            NAME.to_string(),
+            format!("{NAME}.jpg"),
+            format!("{NAME}-thumb"),
+            format!("{}.png", &NAME[1..]),
+            format!("{}.png", NAME.to_uppercase()),
+            format!("../{NAME}.png"),
+            format!("{NAME}-thumb-thumb.png"),
+            ".png".to_string(),
+        ] {
+            assert_eq!(parse_avatar_file(&file), None, "{file} should be rejected");
+        }
+    }
+}
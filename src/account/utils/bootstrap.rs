@@ -0,0 +1,132 @@
+use serde_json::json;
+use sqlx::SqliteConnection;
+
+use crate::{
+    account::{
+        database::{role_repository, user_repository},
+        entities::{
+            role::ADMIN_ROLE,
+            user::{CreateUserEntity, UserEntity},
+        },
+        error::AccountError,
+    },
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        validators::{self, ValidationError},
+        AppConfig,
+    },
+};
+
+use super::{generate_password, hash_password, normalize_email};
+
+pub struct CreatedAdmin {
+    pub user: UserEntity,
+    /// Set when no password was given, so it can be handed to the operator once.
+    pub generated_password: Option<String>,
+}
+
+// Creates the first admin from the config. Does nothing once any admin exists, so it is safe to
+// run on every start.
+pub async fn bootstrap_admin(
+    connection: &mut SqliteConnection,
+    config: &AppConfig,
+) -> ApiResult<Option<CreatedAdmin>> {
+    if user_repository::user_exists_by_role_name(connection, ADMIN_ROLE).await? {
+        return Ok(None);
+    }
+
+    let Some(email) = &config.admin_bootstrap.email else {
+        tracing::warn!(
+            "No admin account exists. Set BOOTSTRAP_ADMIN_EMAIL or run `create-admin <email>` to create one."
+        );
+        return Ok(None);
+    };
+
+    create_admin(
+        connection,
+        config,
+        email,
+        config.admin_bootstrap.password.as_deref(),
+    )
+    .await
+    .map(Some)
+}
+
+// Admins are never promoted from existing accounts here, as anyone could have registered the
+// configured email before the admin was created.
+pub async fn create_admin(
+    connection: &mut SqliteConnection,
+    config: &AppConfig,
+    email: &str,
+    password: Option<&str>,
+) -> ApiResult<CreatedAdmin> {
+    if let Some(errors) = validators::is_email_valid(email) {
+        return Err(ApiError::ValidationError(vec![ValidationError::new(
+            "email", errors,
+        )]));
+    }
+
+    let email = normalize_email(config, email);
+    if user_repository::user_exists_by_email(connection, &email).await? {
+        return Err(ApiError::AccountError(AccountError::UserExistsByEmail(
+            email,
+        )));
+    }
+
+    let generated_password = match password {
+        Some(password) => {
+            if let Some(errors) =
+                validators::is_password_valid(password, Some(&email), &config.password_policy)
+            {
+                return Err(ApiError::ValidationError(vec![ValidationError::new(
+                    "password", errors,
+                )]));
+            }
+            validators::validate_password_not_breached(password, &config.password_policy).await?;
+
+            None
+        }
+        None => Some(generate_password(&config.password_policy)),
+    };
+    let password = password
+        .or(generated_password.as_deref())
+        .unwrap_or_default();
+
+    // The admin role is created by the migrations, so a missing role means a broken database.
+    let role = role_repository::find_role_by_name(connection, ADMIN_ROLE)
+        .await?
+        .ok_or(ApiError::SqlxError(sqlx::Error::RowNotFound))?;
+
+    let user = user_repository::create_user(
+        connection,
+        CreateUserEntity {
+            email,
+            username: None,
+            password: hash_password(&config.argon2, password)?,
+        },
+    )
+    .await?;
+    user_repository::update_role_by_user_id(connection, user.id, role.id).await?;
+
+    audit_repository::create_audit_event(
+        connection,
+        CreateAuditEventEntity::new(AuditEventType::AdminCreated, Some(user.id), None)
+            .with_details(json!({ "role": role.name })),
+    )
+    .await?;
+
+    let user = user_repository::find_user_by_id(connection, user.id)
+        .await?
+        .ok_or(ApiError::AccountError(AccountError::UserDoesNotExistById(
+            user.id,
+        )))?;
+
+    Ok(CreatedAdmin {
+        user,
+        generated_password,
+    })
+}
@@ -1,16 +1,26 @@
+use std::{collections::HashSet, sync::Arc};
+
 use aide::OperationInput;
-use axum::extract::FromRequestParts;
+use axum::extract::{FromRequestParts, OptionalFromRequestParts, OriginalUri};
+use serde_json::json;
 
 use crate::{
     account::{
-        database::{session_repository, user_repository},
+        database::{role_repository, session_repository, user_repository},
         entities::{
-            session::SessionEntity,
-            user::{UserEntity, UserRole},
+            session::{Impersonation, SessionEntity},
+            user::UserEntity,
         },
         error::AccountError,
     },
-    core::{constants::session::headers::SESSION_HEADER_KEY, error::ApiError, AppState},
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+    },
+    core::{
+        constants::session::headers::SESSION_HEADER_KEY, error::ApiError, extractors::client_ip,
+        AppState,
+    },
 };
 
 pub struct PossiblyExpiredSession(pub SessionEntity);
@@ -42,7 +52,54 @@ impl FromRequestParts<AppState> for PossiblyExpiredSession {
 
 impl OperationInput for PossiblyExpiredSession {}
 
-pub struct CurrentUser(pub UserEntity);
+pub struct CurrentSession(pub SessionEntity);
+
+impl FromRequestParts<AppState> for CurrentSession {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        resolve_session(parts, state)
+            .await
+            .map(|session| Self(session.as_ref().clone()))
+    }
+}
+
+impl OperationInput for CurrentSession {}
+
+// Resolved once per request and shared by every extractor that needs the session.
+#[derive(Clone)]
+struct ResolvedSession(Arc<SessionEntity>);
+
+pub async fn resolve_session(
+    parts: &mut axum::http::request::Parts,
+    state: &AppState,
+) -> Result<Arc<SessionEntity>, ApiError> {
+    if let Some(ResolvedSession(session)) = parts.extensions.get::<ResolvedSession>() {
+        return Ok(session.clone());
+    }
+
+    let Some(session_id) = parts.headers.get(SESSION_HEADER_KEY) else {
+        return Err(ApiError::AccountError(AccountError::MissingTokenInHeader));
+    };
+
+    let token = session_id.to_str().map_err(ApiError::from)?;
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+    let Some(session) = session_repository::find_session_by_token(&mut connection, token).await?
+    else {
+        return Err(ApiError::AccountError(AccountError::InvalidOrExpiredToken));
+    };
+
+    let session = Arc::new(session);
+    parts.extensions.insert(ResolvedSession(session.clone()));
+
+    Ok(session)
+}
+
+pub struct CurrentUser(pub UserEntity, pub Option<Impersonation>);
 
 impl FromRequestParts<AppState> for CurrentUser {
     type Rejection = ApiError;
@@ -51,34 +108,78 @@ impl FromRequestParts<AppState> for CurrentUser {
         parts: &mut axum::http::request::Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let Some(session_id) = parts.headers.get(SESSION_HEADER_KEY) else {
-            return Err(ApiError::AccountError(AccountError::MissingTokenInHeader));
+        let session = resolve_session(parts, state).await?;
+        let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+        let Some(user) = user_repository::find_user_by_id(&mut connection, session.user_id).await?
+        else {
+            return Err(ApiError::AccountError(AccountError::InvalidOrExpiredToken));
         };
 
-        let token = session_id.to_str().map_err(ApiError::from)?;
-        let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+        let impersonation = session.impersonator_id.map(|admin_id| Impersonation {
+            session_id: session.id,
+            admin_id,
+        });
 
-        if let Some(user) = user_repository::find_user_by_token(&mut connection, token).await? {
-            Ok(Self(user))
-        } else {
-            Err(ApiError::AccountError(AccountError::InvalidOrExpiredToken))
+        if let Some(impersonation) = &impersonation {
+            audit_repository::create_audit_event(
+                &mut connection,
+                CreateAuditEventEntity::new(
+                    AuditEventType::ImpersonatedRequest,
+                    Some(user.id),
+                    client_ip(parts, &state.config.rate_limit),
+                )
+                .with_actor(impersonation.admin_id)
+                .with_details(json!({
+                    "method": parts.method.as_str(),
+                    "path": parts
+                        .extensions
+                        .get::<OriginalUri>()
+                        .map_or(parts.uri.path(), |uri| uri.path()),
+                })),
+            )
+            .await?;
         }
+
+        Ok(Self(user, impersonation))
     }
 }
 
 impl OperationInput for CurrentUser {}
 
-pub trait Role {
+// Only a missing session header yields `None`; an invalid session is still rejected.
+impl OptionalFromRequestParts<AppState> for CurrentUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &AppState,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key(SESSION_HEADER_KEY) {
+            return Ok(None);
+        }
+
+        <Self as FromRequestParts<AppState>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
+
+pub trait Permission {
     fn get_self() -> Self;
 
-    fn role() -> UserRole;
+    fn permission() -> &'static str;
 }
 
-pub struct CurrentRole<T: Role>(pub T);
+// Resolved once per request and shared by every `RequirePermission` extractor of a handler.
+#[derive(Clone)]
+struct GrantedPermissions(Arc<HashSet<String>>);
+
+pub struct RequirePermission<T: Permission>(pub T);
 
-impl<T> FromRequestParts<AppState> for CurrentRole<T>
+impl<T> FromRequestParts<AppState> for RequirePermission<T>
 where
-    T: Role,
+    T: Permission,
 {
     type Rejection = ApiError;
 
@@ -86,47 +187,117 @@ where
         parts: &mut axum::http::request::Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let Some(session_id) = parts.headers.get(SESSION_HEADER_KEY) else {
-            return Err(ApiError::AccountError(AccountError::MissingTokenInHeader));
-        };
+        let permissions = match parts.extensions.get::<GrantedPermissions>() {
+            Some(permissions) => permissions.clone(),
+            None => {
+                let session = resolve_session(parts, state).await?;
 
-        let token = session_id.to_str().map_err(ApiError::from)?;
-        let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+                // Admin actions are always taken as the admin, never as an impersonated user.
+                if session.impersonator_id.is_some() {
+                    return Err(ApiError::AccountError(
+                        AccountError::ForbiddenWhileImpersonating,
+                    ));
+                }
 
-        let Some(user) = user_repository::find_user_by_token(&mut connection, token).await? else {
-            return Err(ApiError::AccountError(AccountError::InvalidOrExpiredToken));
+                let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+                let Some(user) =
+                    user_repository::find_user_by_id(&mut connection, session.user_id).await?
+                else {
+                    return Err(ApiError::AccountError(AccountError::InvalidOrExpiredToken));
+                };
+
+                let permissions =
+                    role_repository::find_permissions_by_role_id(&mut connection, user.role_id)
+                        .await?
+                        .into_iter()
+                        .collect();
+                let permissions = GrantedPermissions(Arc::new(permissions));
+                parts.extensions.insert(permissions.clone());
+
+                permissions
+            }
         };
 
-        let role = user.role;
-        if role == T::role() {
-            Ok(CurrentRole(T::get_self()))
+        if permissions.0.contains(T::permission()) {
+            Ok(RequirePermission(T::get_self()))
         } else {
-            Err(ApiError::AccountError(AccountError::InsufficientPrivilege))
+            Err(ApiError::AccountError(AccountError::MissingPermission(
+                T::permission().into(),
+            )))
         }
     }
 }
 
-impl<T> OperationInput for CurrentRole<T> where T: Role {}
+impl<T> OperationInput for RequirePermission<T> where T: Permission {}
+
+pub struct ReadUsers;
+
+impl Permission for ReadUsers {
+    fn get_self() -> Self {
+        ReadUsers
+    }
+
+    fn permission() -> &'static str {
+        "users:read"
+    }
+}
+
+pub struct ImpersonateUsers;
+
+impl Permission for ImpersonateUsers {
+    fn get_self() -> Self {
+        ImpersonateUsers
+    }
+
+    fn permission() -> &'static str {
+        "users:impersonate"
+    }
+}
+
+pub struct ReadAuditEvents;
+
+impl Permission for ReadAuditEvents {
+    fn get_self() -> Self {
+        ReadAuditEvents
+    }
+
+    fn permission() -> &'static str {
+        "audit:read"
+    }
+}
+
+pub struct ManageNotifications;
+
+impl Permission for ManageNotifications {
+    fn get_self() -> Self {
+        ManageNotifications
+    }
+
+    fn permission() -> &'static str {
+        "notifications:manage"
+    }
+}
 
-pub struct User;
+pub struct ManageRoles;
 
-impl Role for User {
+impl Permission for ManageRoles {
     fn get_self() -> Self {
-        User
+        ManageRoles
     }
 
-    fn role() -> UserRole {
-        UserRole::User
+    fn permission() -> &'static str {
+        "roles:manage"
     }
 }
-pub struct Admin;
 
-impl Role for Admin {
+pub struct ManageInvitations;
+
+impl Permission for ManageInvitations {
     fn get_self() -> Self {
-        Admin
+        ManageInvitations
     }
 
-    fn role() -> UserRole {
-        UserRole::Admin
+    fn permission() -> &'static str {
+        "invitations:manage"
     }
 }
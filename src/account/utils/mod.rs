@@ -1,14 +1,63 @@
-use argon2::{password_hash::SaltString, Argon2, PasswordHasher};
+use std::{collections::HashMap, sync::OnceLock};
+
+use argon2::{
+    password_hash::SaltString, Algorithm, Argon2, Params, PasswordHash, PasswordHasher,
+    PasswordVerifier, Version,
+};
 use rand::{rngs::OsRng, seq::SliceRandom};
+use sha2::{Digest, Sha256};
+use sqlx::SqliteConnection;
+use strum::EnumIs;
+use uuid::Uuid;
 
-use crate::core::error::{ApiError, ApiResult};
+use crate::{
+    account::{
+        database::{password_history_repository, user_repository},
+        error::AccountError,
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        validators::{self, ValidationError},
+        AppConfig, Argon2Config, OtpAlphabet, PasswordPolicy,
+    },
+    invitation::database::invitation_repository,
+};
 
+pub mod avatar;
+pub mod bootstrap;
 pub mod extractors;
 
-const OTP_ALLOWED_VALUES: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+#[derive(EnumIs)]
+pub enum PasswordMatch {
+    Mismatch,
+    Match,
+    OutdatedMatch,
+}
+
+fn argon2<'a>(config: &Argon2Config, pepper: Option<&'a str>) -> ApiResult<Argon2<'a>> {
+    let params = Params::new(
+        config.memory_cost,
+        config.time_cost,
+        config.parallelism,
+        None,
+    )
+    .map_err(argon2::password_hash::Error::from)?;
 
-pub fn hash_password(password: &str) -> ApiResult<String> {
-    let argon2 = Argon2::default();
+    match pepper {
+        Some(pepper) => Argon2::new_with_secret(
+            pepper.as_bytes(),
+            Algorithm::Argon2id,
+            Version::V0x13,
+            params,
+        )
+        .map_err(argon2::password_hash::Error::from)
+        .map_err(ApiError::from),
+        None => Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params)),
+    }
+}
+
+pub fn hash_password(config: &Argon2Config, password: &str) -> ApiResult<String> {
+    let argon2 = argon2(config, config.pepper.as_deref())?;
     let salt = SaltString::generate(&mut OsRng);
     argon2
         .hash_password(password.as_bytes(), &salt)
@@ -16,14 +65,222 @@ pub fn hash_password(password: &str) -> ApiResult<String> {
         .map_err(ApiError::from)
 }
 
-pub fn generate_otp(length: usize) -> String {
-    let mut rng = rand::thread_rng();
-    let mut values = Vec::from(OTP_ALLOWED_VALUES);
-    values.shuffle(&mut rng);
+pub fn verify_password(
+    config: &Argon2Config,
+    password: &str,
+    hash: &str,
+) -> ApiResult<PasswordMatch> {
+    let hash = PasswordHash::new(hash).map_err(ApiError::from)?;
+
+    if verify_password_with_pepper(config, config.pepper.as_deref(), password, &hash)? {
+        if is_hash_outdated(config, &hash) {
+            Ok(PasswordMatch::OutdatedMatch)
+        } else {
+            Ok(PasswordMatch::Match)
+        }
+    } else if config.pepper.is_some() && verify_password_with_pepper(config, None, password, &hash)?
+    {
+        // Hashes created before a pepper was configured can only be verified without it.
+        Ok(PasswordMatch::OutdatedMatch)
+    } else {
+        Ok(PasswordMatch::Mismatch)
+    }
+}
+
+// Spends the same time as verifying a real hash, so callers can respond to unknown accounts
+// as slowly as they respond to known ones.
+pub fn dummy_verify_password(config: &Argon2Config, password: &str) {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+
+    let hash = DUMMY_HASH
+        .get_or_init(|| hash_password(config, &Uuid::new_v4().to_string()).unwrap_or_default());
+    let _ = verify_password(config, password, hash);
+}
+
+fn verify_password_with_pepper(
+    config: &Argon2Config,
+    pepper: Option<&str>,
+    password: &str,
+    hash: &PasswordHash,
+) -> ApiResult<bool> {
+    match argon2(config, pepper)?.verify_password(password.as_bytes(), hash) {
+        Ok(()) => Ok(true),
+        Err(argon2::password_hash::Error::Password) => Ok(false),
+        Err(error) => Err(ApiError::from(error)),
+    }
+}
+
+fn is_hash_outdated(config: &Argon2Config, hash: &PasswordHash) -> bool {
+    let Ok(params) = Params::try_from(hash) else {
+        return true;
+    };
+
+    hash.algorithm != Algorithm::Argon2id.ident()
+        || hash.version != Some(Version::V0x13.into())
+        || params.m_cost() != config.memory_cost
+        || params.t_cost() != config.time_cost
+        || params.p_cost() != config.parallelism
+}
 
-    values
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
         .iter()
-        .take(length)
-        .map(|val| val.to_string())
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+pub async fn update_password(
+    connection: &mut SqliteConnection,
+    config: &AppConfig,
+    user_id: i64,
+    password: &str,
+) -> ApiResult<()> {
+    let policy = &config.password_policy;
+
+    validators::validate_password_not_breached(password, policy).await?;
+
+    let Some(user) = user_repository::find_user_by_id(connection, user_id).await? else {
+        return Err(ApiError::AccountError(AccountError::UserDoesNotExistById(
+            user_id,
+        )));
+    };
+
+    if policy.history_size > 0 {
+        let previous_passwords = password_history_repository::find_recent_passwords_by_user_id(
+            connection,
+            user_id,
+            policy.history_size as i64 - 1,
+        )
+        .await?;
+
+        for previous_password in std::iter::once(&user.password).chain(&previous_passwords) {
+            if !verify_password(&config.argon2, password, previous_password)?.is_mismatch() {
+                return Err(ApiError::ValidationError(vec![ValidationError::new(
+                    "password",
+                    vec![format!(
+                        "Password must not be the same as any of your last {} passwords.",
+                        policy.history_size
+                    )],
+                )]));
+            }
+        }
+
+        password_history_repository::create_password_history(connection, user_id, &user.password)
+            .await?;
+        password_history_repository::prune_password_history(
+            connection,
+            user_id,
+            policy.history_size as i64 - 1,
+        )
+        .await?;
+    }
+
+    let hash = hash_password(&config.argon2, password)?;
+    user_repository::update_password_by_user_id(connection, user_id, &hash).await
+}
+
+// Emails are stored and looked up in this form, so addresses differing only in case or in the
+// encoding of an internationalized domain resolve to the same account.
+pub fn normalize_email(config: &AppConfig, email: &str) -> String {
+    let email = email.trim();
+    let Some((local_part, domain)) = email.rsplit_once('@') else {
+        return email.to_string();
+    };
+
+    let domain = idna::domain_to_ascii(domain).unwrap_or_else(|_| domain.to_lowercase());
+    let local_part = if config.lowercase_email_local_part {
+        local_part.to_lowercase()
+    } else {
+        local_part.to_string()
+    };
+
+    format!("{local_part}@{domain}")
+}
+
+// Rewrites stored emails into the form of `normalize_email`, which SQL alone cannot reproduce.
+// Accounts whose emails normalize to the same address are left untouched and reported in
+// `user_email_collisions` instead. Returns the number of reported accounts.
+pub async fn normalize_stored_emails(
+    connection: &mut SqliteConnection,
+    config: &AppConfig,
+) -> ApiResult<usize> {
+    let mut users_by_email = HashMap::<String, Vec<(i64, String)>>::new();
+    for (id, email) in user_repository::find_all_emails(connection).await? {
+        users_by_email
+            .entry(normalize_email(config, &email))
+            .or_default()
+            .push((id, email));
+    }
+
+    user_repository::delete_email_collisions(connection).await?;
+
+    let mut collisions = 0;
+    for (normalized_email, users) in users_by_email {
+        if users.len() > 1 {
+            for (id, email) in &users {
+                user_repository::create_email_collision(connection, *id, email, &normalized_email)
+                    .await?;
+            }
+            collisions += users.len();
+        } else if let Some((id, email)) = users
+            .first()
+            .filter(|(_, email)| *email != normalized_email)
+        {
+            user_repository::update_email_by_user_id(connection, *id, &normalized_email).await?;
+            tracing::info!("Normalized the email of user {id} from {email} to {normalized_email}");
+        }
+    }
+
+    for (id, email) in invitation_repository::find_all_emails(connection).await? {
+        let normalized_email = normalize_email(config, &email);
+        if email != normalized_email {
+            invitation_repository::update_invitation_email(connection, id, &normalized_email)
+                .await?;
+        }
+    }
+
+    Ok(collisions)
+}
+
+pub fn generate_otp(alphabet: OtpAlphabet, length: usize) -> String {
+    let characters = alphabet.characters();
+
+    (0..length)
+        .map(|_| *characters.choose(&mut OsRng).unwrap() as char)
         .collect()
 }
+
+// Generates a password that satisfies the password policy, e.g. for bootstrapped accounts.
+pub fn generate_password(policy: &PasswordPolicy) -> String {
+    const CHARACTER_SETS: [&[u8]; 4] = [
+        b"abcdefghijkmnopqrstuvwxyz",
+        b"ABCDEFGHJKLMNPQRSTUVWXYZ",
+        b"23456789",
+        b"!#$%&*+-=?@^_",
+    ];
+    let length = policy.min_length.max(24).min(policy.max_length);
+    let all_characters = CHARACTER_SETS.concat();
+
+    for _ in 0..100 {
+        // One character of every kind first, so all composition rules are always met.
+        let mut password = CHARACTER_SETS
+            .iter()
+            .map(|characters| *characters.choose(&mut OsRng).unwrap())
+            .collect::<Vec<u8>>();
+        while password.len() < length {
+            password.push(*all_characters.choose(&mut OsRng).unwrap());
+        }
+        password.shuffle(&mut OsRng);
+
+        let password = String::from_utf8(password).unwrap();
+        if validators::is_password_valid(&password, None, policy).is_none() {
+            return password;
+        }
+    }
+
+    panic!("Failed to generate a password that satisfies the password policy.");
+}
+
+pub fn normalize_otp(otp: &str) -> String {
+    otp.trim().to_uppercase()
+}
@@ -0,0 +1,81 @@
+use sqlx::{Sqlite, SqliteConnection};
+
+use crate::{
+    audit::entities::audit_event::{AuditEventEntity, AuditEventFilter, CreateAuditEventEntity},
+    core::{
+        error::{ApiError, ApiResult},
+        models::{Page, PageRequest},
+    },
+};
+
+const AUDIT_EVENT_FILTER: &str = "
+    WHERE (?1 IS NULL OR e.user_id = ?1)
+      AND (?2 IS NULL OR e.event_type = ?2)
+      AND (?3 IS NULL OR e.created_at >= ?3)
+      AND (?4 IS NULL OR e.created_at <= ?4)
+    ";
+
+pub async fn create_audit_event(
+    connection: &mut SqliteConnection,
+    event: CreateAuditEventEntity,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "
+        INSERT INTO audit_events (event_type, user_id, actor_id, ip_address, details)
+        VALUES (?, ?, ?, ?, ?)
+        ",
+        event.event_type,
+        event.user_id,
+        event.actor_id,
+        event.ip_address,
+        event.details
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn find_paginated_audit_events(
+    connection: &mut SqliteConnection,
+    filter: AuditEventFilter,
+    request: PageRequest,
+) -> ApiResult<Page<AuditEventEntity>> {
+    let sql = request.to_sql_string(&format!(
+        "
+        SELECT
+            e.id,
+            e.created_at,
+            e.event_type,
+            e.user_id,
+            e.actor_id,
+            e.ip_address,
+            e.details
+        FROM audit_events e
+        {AUDIT_EVENT_FILTER}
+        "
+    ));
+
+    let events = sqlx::query_as::<Sqlite, AuditEventEntity>(&sql)
+        .bind(filter.user_id)
+        .bind(filter.event_type)
+        .bind(filter.from.clone())
+        .bind(filter.to.clone())
+        .fetch_all(&mut *connection)
+        .await
+        .map_err(ApiError::from)?;
+
+    let count = sqlx::query_scalar::<Sqlite, i64>(&format!(
+        "SELECT COUNT(1) FROM audit_events e {AUDIT_EVENT_FILTER}"
+    ))
+    .bind(filter.user_id)
+    .bind(filter.event_type)
+    .bind(filter.from)
+    .bind(filter.to)
+    .fetch_one(connection)
+    .await
+    .map(|count| count as u64)
+    .map_err(ApiError::from)?;
+
+    Ok(Page::new(events, count, request))
+}
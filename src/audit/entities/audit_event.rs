@@ -0,0 +1,88 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+
+use crate::core::types::DbDateTime;
+
+#[derive(Clone, Copy, Type, Serialize, Deserialize, JsonSchema)]
+pub enum AuditEventType {
+    LoginSucceeded,
+    LoginFailed,
+    AccountLocked,
+    LoggedOut,
+    SessionExtended,
+    OtpRequested,
+    OtpVerified,
+    OtpVerificationFailed,
+    PasswordReset,
+    RoleChanged,
+    AdminAction,
+    ImpersonationStarted,
+    ImpersonationEnded,
+    ImpersonatedRequest,
+    OrganizationCreated,
+    OrganizationMemberAdded,
+    OrganizationMemberRoleChanged,
+    OrganizationMemberRemoved,
+    InvitationCreated,
+    InvitationRevoked,
+    InvitationAccepted,
+    AdminCreated,
+    SessionsRevoked,
+}
+
+#[derive(FromRow, Clone)]
+pub struct AuditEventEntity {
+    pub id: i64,
+    pub created_at: DbDateTime,
+    pub event_type: AuditEventType,
+    pub user_id: Option<i64>,
+    pub actor_id: Option<i64>,
+    pub ip_address: Option<String>,
+    pub details: Option<String>,
+}
+
+pub struct CreateAuditEventEntity {
+    pub event_type: AuditEventType,
+    pub user_id: Option<i64>,
+    pub actor_id: Option<i64>,
+    pub ip_address: Option<String>,
+    pub details: Option<String>,
+}
+
+impl CreateAuditEventEntity {
+    pub fn new(
+        event_type: AuditEventType,
+        user_id: Option<i64>,
+        ip_address: Option<String>,
+    ) -> Self {
+        Self {
+            event_type,
+            user_id,
+            actor_id: None,
+            ip_address,
+            details: None,
+        }
+    }
+
+    pub fn with_actor(self, actor_id: i64) -> Self {
+        Self {
+            actor_id: Some(actor_id),
+            ..self
+        }
+    }
+
+    pub fn with_details(self, details: serde_json::Value) -> Self {
+        Self {
+            details: Some(details.to_string()),
+            ..self
+        }
+    }
+}
+
+pub struct AuditEventFilter {
+    pub user_id: Option<i64>,
+    pub event_type: Option<AuditEventType>,
+    pub from: Option<DbDateTime>,
+    pub to: Option<DbDateTime>,
+}
@@ -0,0 +1,46 @@
+use axum::extract::State;
+use axum_extra::extract::Query;
+use serde_json::json;
+
+use crate::{
+    account::utils::extractors::{CurrentUser, ReadAuditEvents, RequirePermission},
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+        models::{
+            request::audit_event::AuditEventFilterRequest,
+            response::audit_event::AuditEventResponse,
+        },
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        extractors::{ClientIp, JsonResponse},
+        models::{Page, PageRequest},
+        AppState,
+    },
+};
+
+#[axum::debug_handler]
+pub async fn get_paginated_audit_events(
+    State(state): State<AppState>,
+    CurrentUser(admin, _): CurrentUser,
+    RequirePermission(_): RequirePermission<ReadAuditEvents>,
+    ClientIp(ip_address): ClientIp,
+    Query(filter): Query<AuditEventFilterRequest>,
+    Query(request): Query<PageRequest>,
+) -> ApiResult<JsonResponse<Page<AuditEventResponse>>> {
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(AuditEventType::AdminAction, None, ip_address)
+            .with_actor(admin.id)
+            .with_details(json!({ "action": "list_audit_events" })),
+    )
+    .await?;
+
+    audit_repository::find_paginated_audit_events(&mut connection, filter.into(), request)
+        .await
+        .map(|events| events.map(|event| event.to_owned().into()))
+        .map(JsonResponse)
+}
@@ -0,0 +1,7 @@
+pub mod database;
+pub mod entities;
+pub mod handlers;
+pub mod models;
+mod router;
+
+pub use router::*;
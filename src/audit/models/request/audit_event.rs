@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::audit::entities::audit_event::{AuditEventFilter, AuditEventType};
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEventFilterRequest {
+    pub user_id: Option<i64>,
+    pub event_type: Option<AuditEventType>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl From<AuditEventFilterRequest> for AuditEventFilter {
+    fn from(value: AuditEventFilterRequest) -> Self {
+        Self {
+            user_id: value.user_id,
+            event_type: value.event_type,
+            from: value.from.map(Into::into),
+            to: value.to.map(Into::into),
+        }
+    }
+}
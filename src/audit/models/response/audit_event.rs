@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::audit::entities::audit_event::{AuditEventEntity, AuditEventType};
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEventResponse {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub event_type: AuditEventType,
+    pub user_id: Option<i64>,
+    pub actor_id: Option<i64>,
+    pub ip_address: Option<String>,
+    pub details: Option<serde_json::Value>,
+}
+
+impl From<AuditEventEntity> for AuditEventResponse {
+    fn from(value: AuditEventEntity) -> Self {
+        Self {
+            id: value.id,
+            created_at: value.created_at.into(),
+            event_type: value.event_type,
+            user_id: value.user_id,
+            actor_id: value.actor_id,
+            ip_address: value.ip_address,
+            details: value
+                .details
+                .and_then(|details| serde_json::from_str(&details).ok()),
+        }
+    }
+}
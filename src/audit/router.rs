@@ -0,0 +1,27 @@
+use aide::axum::{routing::get, ApiRouter};
+
+use crate::core::{
+    constants::openapi::{tags::admin, DEFAULT_SECURITY_SCHEME},
+    AppState,
+};
+
+use super::handlers;
+
+pub fn router(state: AppState) -> ApiRouter {
+    ApiRouter::new().nest(
+        "/audit",
+        ApiRouter::new()
+            .nest(
+                "/admin/events",
+                ApiRouter::new().api_route_with(
+                    "/",
+                    get(handlers::admin::get_paginated_audit_events),
+                    |op| {
+                        op.tag(admin::AUDIT_TAG)
+                            .security_requirement(DEFAULT_SECURITY_SCHEME)
+                    },
+                ),
+            )
+            .with_state(state.clone()),
+    )
+}
@@ -1,4 +1,10 @@
-use std::sync::Arc;
+use std::{
+    fs::OpenOptions,
+    io::{IsTerminal, Write},
+    net::SocketAddr,
+    path::Path,
+    sync::Arc,
+};
 
 use aide::{
     axum::{routing::get, ApiRouter, IntoApiResponse},
@@ -9,17 +15,35 @@ use aide::{
 use axum::{response::IntoResponse, Extension, Router};
 use tokio::net::TcpListener;
 
-use crate::account;
+use crate::{
+    account::{
+        self,
+        database::user_repository,
+        utils::bootstrap::{self, CreatedAdmin},
+    },
+    audit, invitation,
+    notification::{self, dispatcher::NotificationDispatcher},
+    organization,
+};
 
-use super::{extractors::JsonResponse, AppConfig, AppState};
+use super::{
+    error::{ApiError, ApiResult},
+    extractors::JsonResponse,
+    migrations::{self, MigrationState},
+    AppConfig, AppState,
+};
 
 pub struct App;
 
 impl App {
     pub async fn serve() {
-        tracing_subscriber::fmt::init();
-
         let config = AppConfig::new();
+        config.invitation.assert_secret();
+        if config.migrations.run_on_startup {
+            migrations::create_database_if_missing(&config.db_url)
+                .await
+                .unwrap_or_else(|err| panic!("Failed to create database: {err}"));
+        }
         let state = AppState::new(config.clone()).await;
 
         let host = config.host;
@@ -28,14 +52,179 @@ impl App {
         let address = format!("{host}:{port}");
         let listener = TcpListener::bind(address.clone())
             .await
-            .expect(format!("Failed to bind to {address}").as_str());
+            .unwrap_or_else(|_| panic!("Failed to bind to {address}"));
+
+        migrate_database(&state).await;
+        report_email_collisions(&state).await;
+        bootstrap_admin(&state).await;
 
-        let app = setup_router(vec![account::router(state)]);
+        NotificationDispatcher::new(&state).spawn();
+
+        let app = setup_router(routers(state));
 
         tracing::info!("Serving app at {address}");
-        axum::serve(listener, app.into_make_service())
-            .await
-            .expect("Failed to serve app");
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .expect("Failed to serve app");
+    }
+
+    pub fn openapi(state: AppState) -> OpenApi {
+        let mut api = OpenApi::default();
+        let mut app = ApiRouter::new();
+
+        for router in routers(state) {
+            app = app.merge(router)
+        }
+
+        let _ = app.finish_api_with(&mut api, api_docs);
+        api
+    }
+}
+
+fn routers(state: AppState) -> Vec<ApiRouter> {
+    vec![
+        account::router(state.clone()),
+        audit::router(state.clone()),
+        notification::router(state.clone()),
+        organization::router(state.clone()),
+        invitation::router(state),
+    ]
+}
+
+// Refuses to start against a database migrated by a newer version, as this version could corrupt
+// data it does not know the shape of.
+async fn migrate_database(state: &AppState) {
+    let statuses = migrations::status(&state.pool)
+        .await
+        .unwrap_or_else(|err| panic!("Failed to read the applied migrations: {err}"));
+
+    let unknown = statuses
+        .iter()
+        .filter(|status| matches!(status.state, MigrationState::Unknown))
+        .map(|status| status.version.to_string())
+        .collect::<Vec<String>>();
+    if !unknown.is_empty() {
+        panic!(
+            "The database is ahead of this version, migration(s) {} are unknown. Refusing to start.",
+            unknown.join(", ")
+        );
+    }
+
+    let pending = statuses
+        .iter()
+        .filter(|status| matches!(status.state, MigrationState::Pending))
+        .collect::<Vec<_>>();
+    if pending.is_empty() {
+        tracing::info!("Database is up to date");
+        return;
+    }
+
+    if !state.config.migrations.run_on_startup {
+        tracing::warn!(
+            "{} pending migration(s). Run `migrate up` or set MIGRATE_ON_STARTUP=true.",
+            pending.len()
+        );
+        return;
+    }
+
+    for status in &pending {
+        tracing::info!(
+            "Applying migration {} {}",
+            status.version,
+            status.description
+        );
+    }
+    migrations::run(&state.pool, &state.config.migrations)
+        .await
+        .unwrap_or_else(|err| panic!("Failed to migrate the database: {err}"));
+    tracing::info!("Applied {} migration(s)", pending.len());
+
+    normalize_stored_emails(state)
+        .await
+        .unwrap_or_else(|err| panic!("Failed to normalize stored emails: {err:?}"));
+}
+
+// Runs after migrating, so emails stored by older versions follow the current normalization.
+pub(super) async fn normalize_stored_emails(state: &AppState) -> ApiResult<()> {
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+    account::utils::normalize_stored_emails(&mut connection, &state.config).await?;
+    connection.commit().await.map_err(ApiError::from)
+}
+
+// Creates the first admin on a fresh database, see `BOOTSTRAP_ADMIN_EMAIL`.
+async fn bootstrap_admin(state: &AppState) {
+    let result = match state.pool.begin().await {
+        Ok(mut connection) => {
+            match bootstrap::bootstrap_admin(&mut connection, &state.config).await {
+                Ok(admin) => connection.commit().await.map(|_| admin).map_err(Into::into),
+                Err(err) => Err(err),
+            }
+        }
+        Err(err) => Err(err.into()),
+    };
+
+    match result {
+        Ok(Some(admin)) => print_created_admin(&state.config, &admin),
+        Ok(None) => {}
+        Err(err) => panic!("Failed to bootstrap admin: {err:?}"),
+    }
+}
+
+// The generated password never goes through tracing, as logs are usually collected and kept.
+fn print_created_admin(config: &AppConfig, admin: &CreatedAdmin) {
+    let Some(password) = &admin.generated_password else {
+        tracing::info!("Created admin {}.", admin.user.email);
+        return;
+    };
+
+    if std::io::stderr().is_terminal() {
+        eprintln!(
+            "Created admin {} with generated password {password}. Change it after signing in, it will not be shown again.",
+            admin.user.email
+        );
+        return;
+    }
+
+    let path = &config.admin_bootstrap.password_file;
+    match write_private_file(path, password) {
+        Ok(()) => tracing::warn!(
+            "Created admin {} with a generated password written to {}. Delete the file after signing in.",
+            admin.user.email,
+            path.display()
+        ),
+        Err(err) => tracing::error!(
+            "Created admin {} but failed to write its generated password to {}: {err}. Run `reset-password` to set a new one.",
+            admin.user.email,
+            path.display()
+        ),
+    }
+}
+
+pub(super) fn write_private_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    writeln!(options.open(path)?, "{contents}")
+}
+
+// Accounts whose emails could not be normalized need to be resolved by hand.
+async fn report_email_collisions(state: &AppState) {
+    let count = match state.pool.acquire().await {
+        Ok(mut connection) => user_repository::count_email_collisions(&mut connection).await,
+        Err(err) => Err(err.into()),
+    };
+
+    match count {
+        Ok(0) => {}
+        Ok(count) => tracing::warn!(
+            "{count} account(s) have emails that could not be normalized, see the user_email_collisions table."
+        ),
+        Err(err) => tracing::error!("Failed to check for email collisions: {err:?}"),
     }
 }
 
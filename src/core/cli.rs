@@ -0,0 +1,340 @@
+use std::{
+    error::Error,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use clap::{Parser, Subcommand};
+use serde_json::json;
+use sqlx::{sqlite::SqliteConnectOptions, SqliteConnection, SqlitePool};
+
+use crate::{
+    account::{
+        database::{session_repository, user_repository},
+        entities::{session::RevocationReason, user::UserEntity},
+        error::AccountError,
+        utils::{bootstrap, generate_password, normalize_email, update_password},
+    },
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+    },
+    notification::{
+        dispatcher,
+        entities::notification::{CreateNotificationEntity, NotificationType},
+    },
+};
+
+use super::{
+    app,
+    error::{ApiError, ApiResult},
+    migrations::{self, MigrationState},
+    App, AppConfig, AppState,
+};
+
+type CommandResult = Result<(), Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serves the API. This is the default when no command is given.
+    Serve,
+    /// Applies, reverts or lists the database migrations.
+    Migrate {
+        #[command(subcommand)]
+        command: MigrateCommand,
+    },
+    /// Creates an admin account with a generated password.
+    CreateAdmin { email: String },
+    /// Sets a generated password for an account and signs it out everywhere.
+    ResetPassword { email: String },
+    /// Signs an account out of every session.
+    RevokeSessions { email: String },
+    /// Works with the OpenAPI document of the API.
+    Openapi {
+        #[command(subcommand)]
+        command: OpenapiCommand,
+    },
+    /// Works with the configuration read from the environment.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum MigrateCommand {
+    /// Applies every pending migration, creating the database if needed.
+    Up,
+    /// Reverts the latest migration.
+    Down {
+        /// Reverts every migration newer than this version instead.
+        #[arg(long)]
+        target: Option<i64>,
+    },
+    /// Lists the migrations and whether they have been applied.
+    Status,
+}
+
+#[derive(Subcommand)]
+enum OpenapiCommand {
+    /// Writes the OpenAPI document as JSON.
+    Export {
+        /// File to write to instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Validates the configuration and checks that the database is reachable.
+    Check,
+}
+
+impl Cli {
+    pub async fn run(self) {
+        let command = self.command.unwrap_or(Command::Serve);
+
+        // Logs of one-off commands go to stderr so their output can be piped.
+        if matches!(command, Command::Serve) {
+            tracing_subscriber::fmt::init();
+        } else {
+            tracing_subscriber::fmt()
+                .with_writer(std::io::stderr)
+                .init();
+        }
+
+        let result = match command {
+            Command::Serve => {
+                App::serve().await;
+                Ok(())
+            }
+            Command::Migrate { command } => migrate(command).await,
+            Command::CreateAdmin { email } => create_admin(&email).await,
+            Command::ResetPassword { email } => reset_password(&email).await,
+            Command::RevokeSessions { email } => revoke_sessions(&email).await,
+            Command::Openapi {
+                command: OpenapiCommand::Export { output },
+            } => export_openapi(output).await,
+            Command::Config {
+                command: ConfigCommand::Check,
+            } => check_config().await,
+        };
+
+        if let Err(err) = result {
+            tracing::error!("{err:?}");
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn migrate(command: MigrateCommand) -> CommandResult {
+    let config = AppConfig::new();
+    if matches!(command, MigrateCommand::Up) {
+        migrations::create_database_if_missing(&config.db_url).await?;
+    }
+    let state = AppState::new(config).await;
+
+    match command {
+        MigrateCommand::Up => {
+            migrations::run(&state.pool, &state.config.migrations).await?;
+            app::normalize_stored_emails(&state).await?;
+            println!("Database is up to date.");
+        }
+        MigrateCommand::Down { target } => {
+            match migrations::undo(&state.pool, target, &state.config.migrations).await? {
+                Some(target) => println!("Reverted migrations newer than {target}."),
+                None => println!("No migrations to revert."),
+            }
+        }
+        MigrateCommand::Status => {
+            for status in migrations::status(&state.pool).await? {
+                let state = match status.state {
+                    MigrationState::Applied => "applied",
+                    MigrationState::Pending => "pending",
+                    MigrationState::ChecksumMismatch => "applied, checksum mismatch",
+                    MigrationState::Unknown => "applied, unknown to this version",
+                };
+                println!("{} {:<50} {state}", status.version, status.description);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn create_admin(email: &str) -> CommandResult {
+    let config = AppConfig::new();
+    let state = AppState::new(config.clone()).await;
+    let mut connection = state.pool.begin().await?;
+
+    let admin = bootstrap::create_admin(&mut connection, &config, email, None).await?;
+    let password_file = match &admin.generated_password {
+        Some(password) => save_password_unless_terminal(&config, password)?,
+        None => None,
+    };
+    connection.commit().await?;
+
+    println!("Created admin {}.", admin.user.email);
+    if let Some(password) = &admin.generated_password {
+        print_password(password, password_file);
+    }
+
+    Ok(())
+}
+
+async fn reset_password(email: &str) -> CommandResult {
+    let config = AppConfig::new();
+    let state = AppState::new(config.clone()).await;
+    let mut connection = state.pool.begin().await?;
+
+    let user = find_user(&mut connection, &config, email).await?;
+    let password = generate_password(&config.password_policy);
+    update_password(&mut connection, &config, user.id, &password).await?;
+    session_repository::revoke_session_for_user_id(
+        &mut connection,
+        user.id,
+        RevocationReason::PasswordReset,
+    )
+    .await?;
+
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(AuditEventType::PasswordReset, Some(user.id), None)
+            .with_details(json!({ "source": "cli" })),
+    )
+    .await?;
+    dispatcher::notify(
+        &mut connection,
+        &config,
+        CreateNotificationEntity::new(NotificationType::PasswordChanged, user.id),
+    )
+    .await?;
+    let password_file = save_password_unless_terminal(&config, &password)?;
+    connection.commit().await?;
+
+    println!(
+        "Reset the password of {} and revoked their sessions.",
+        user.email
+    );
+    print_password(&password, password_file);
+
+    Ok(())
+}
+
+// Generated passwords are only printed to a terminal, as redirected output tends to end up in
+// logs. Otherwise they go to a file only the current user can read, written before the change is
+// committed so a failed write never leaves an account with a password nobody knows.
+fn save_password_unless_terminal<'a>(
+    config: &'a AppConfig,
+    password: &str,
+) -> Result<Option<&'a Path>, Box<dyn Error>> {
+    if std::io::stdout().is_terminal() {
+        return Ok(None);
+    }
+
+    let path = &config.admin_bootstrap.password_file;
+    app::write_private_file(path, password)
+        .map_err(|err| format!("Failed to write the password to {}: {err}", path.display()))?;
+
+    Ok(Some(path))
+}
+
+fn print_password(password: &str, password_file: Option<&Path>) {
+    match password_file {
+        Some(path) => println!(
+            "The password was written to {}. Delete the file once it has been used.",
+            path.display()
+        ),
+        None => println!("Password: {password}"),
+    }
+}
+
+async fn revoke_sessions(email: &str) -> CommandResult {
+    let config = AppConfig::new();
+    let state = AppState::new(config.clone()).await;
+    let mut connection = state.pool.begin().await?;
+
+    let user = find_user(&mut connection, &config, email).await?;
+    session_repository::revoke_session_for_user_id(
+        &mut connection,
+        user.id,
+        RevocationReason::Revoked,
+    )
+    .await?;
+
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(AuditEventType::SessionsRevoked, Some(user.id), None)
+            .with_details(json!({ "source": "cli" })),
+    )
+    .await?;
+    connection.commit().await?;
+
+    println!("Revoked every session of {}.", user.email);
+
+    Ok(())
+}
+
+async fn export_openapi(output: Option<PathBuf>) -> CommandResult {
+    let config = AppConfig::new();
+    let state = AppState::without_connecting(config);
+
+    let api = serde_json::to_string_pretty(&App::openapi(state))?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, api)?;
+            println!("Wrote OpenAPI document to {}.", path.display());
+        }
+        None => println!("{api}"),
+    }
+
+    Ok(())
+}
+
+// Invalid configuration panics with a message pointing at the offending variable while loading.
+async fn check_config() -> CommandResult {
+    let config = AppConfig::new();
+
+    // Checking must never change the database, not even by creating the migrations table.
+    let options = SqliteConnectOptions::from_str(&config.db_url)?.read_only(true);
+    let pool = SqlitePool::connect_with(options).await?;
+    let statuses = migrations::status(&pool).await?;
+    let pending = statuses
+        .iter()
+        .filter(|status| matches!(status.state, MigrationState::Pending))
+        .count();
+
+    println!("Configuration is valid.");
+    println!(
+        "Serving at {}:{} ({}).",
+        config.host, config.port, config.public_url
+    );
+    println!(
+        "Database {} is reachable, {pending} pending migration(s).",
+        config.db_url
+    );
+
+    Ok(())
+}
+
+async fn find_user(
+    connection: &mut SqliteConnection,
+    config: &AppConfig,
+    email: &str,
+) -> ApiResult<UserEntity> {
+    let email = normalize_email(config, email);
+    user_repository::find_user_by_email(connection, &email)
+        .await?
+        .ok_or(ApiError::AccountError(
+            AccountError::UserDoesNotExistByEmail(email),
+        ))
+}
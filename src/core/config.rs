@@ -2,16 +2,25 @@ use std::{
     env,
     fmt::{Debug, Display},
     ops::Range,
+    path::PathBuf,
     str::FromStr,
 };
 
 use chrono::Duration;
 use strum::{Display, EnumIs, EnumString};
 
+use crate::{
+    account::entities::preference::{EmailDigest, PreferencesEntity, Theme},
+    notification::mailer::MailerKind,
+};
+
+use super::{rate_limit::RateLimitStoreKind, storage::StorageKind, utils::is_prod};
+
 #[derive(Clone)]
 pub struct AppConfig {
     pub host: String,
     pub port: String,
+    pub public_url: String,
     pub db_url: String,
     pub show_sql: bool,
 
@@ -19,7 +28,166 @@ pub struct AppConfig {
     pub status_code_range_for_error_logging: Range<u16>,
     pub session_duration: Duration,
     pub session_refresh_duration: Duration,
+    pub impersonation_duration: Duration,
     pub otp_validity_duration: Duration,
+    pub otp_max_attempts: i64,
+    pub otp_length: usize,
+    pub otp_alphabet: OtpAlphabet,
+    pub anti_enumeration: bool,
+    pub lowercase_email_local_part: bool,
+    pub password_policy: PasswordPolicy,
+    pub registration_policy: RegistrationPolicy,
+    pub argon2: Argon2Config,
+    pub rate_limit: RateLimitConfig,
+    pub notification: NotificationConfig,
+    pub invitation: InvitationConfig,
+    pub storage: StorageConfig,
+    pub avatar: AvatarConfig,
+    pub preferences: PreferencesConfig,
+    pub admin_bootstrap: AdminBootstrapConfig,
+    pub migrations: MigrationConfig,
+}
+
+#[derive(Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_special: bool,
+    pub forbid_email: bool,
+    pub min_strength: u8,
+    pub breached_passwords_dir: Option<PathBuf>,
+    pub history_size: u32,
+}
+
+#[derive(Clone)]
+pub struct RegistrationPolicy {
+    pub open: bool,
+    pub allowed_domains: Vec<String>,
+    pub denied_domains: Vec<String>,
+    pub block_disposable: bool,
+}
+
+#[derive(Clone)]
+pub struct Argon2Config {
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+    pub pepper: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub store: RateLimitStoreKind,
+    pub trust_forwarded_for: bool,
+    pub trusted_proxies: usize,
+}
+
+#[derive(Clone)]
+pub struct NotificationConfig {
+    pub enabled: bool,
+    pub mailer: MailerKind,
+    pub smtp_url: Option<String>,
+    pub from: String,
+    pub poll_interval: Duration,
+    pub max_attempts: i64,
+}
+
+const MIN_INVITATION_SECRET_LENGTH: usize = 32;
+
+// Markers of sample values copied from docs or examples, e.g. `change-me-to-a-random-string`.
+const INVITATION_SECRET_PLACEHOLDERS: [&str; 4] =
+    ["change-me", "change_me", "changeme", "placeholder"];
+
+#[derive(Clone)]
+pub struct InvitationConfig {
+    // Only required to serve the API, so commands such as `openapi export` run without it.
+    secret: Option<String>,
+    pub validity_duration: Duration,
+}
+
+impl InvitationConfig {
+    pub fn secret(&self) -> &str {
+        self.secret
+            .as_deref()
+            .expect("INVITATION_SECRET is checked before serving")
+    }
+
+    pub fn assert_secret(&self) {
+        let secret = self
+            .secret
+            .as_deref()
+            .expect("INVITATION_SECRET must be set.");
+
+        let lowercase = secret.to_lowercase();
+        assert!(
+            !INVITATION_SECRET_PLACEHOLDERS
+                .iter()
+                .any(|placeholder| lowercase.contains(placeholder)),
+            "INVITATION_SECRET must not be a placeholder value."
+        );
+        assert!(
+            secret.len() >= MIN_INVITATION_SECRET_LENGTH,
+            "INVITATION_SECRET must be at least {MIN_INVITATION_SECRET_LENGTH} characters long."
+        );
+    }
+}
+
+#[derive(Clone)]
+pub struct StorageConfig {
+    pub backend: StorageKind,
+    pub local_dir: PathBuf,
+}
+
+#[derive(Clone)]
+pub struct AvatarConfig {
+    pub max_upload_size: usize,
+    pub max_dimension: u32,
+    pub size: u32,
+    pub thumbnail_size: u32,
+}
+
+#[derive(Clone)]
+pub struct PreferencesConfig {
+    pub defaults: PreferencesEntity,
+    pub beta_features: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct AdminBootstrapConfig {
+    pub email: Option<String>,
+    pub password: Option<String>,
+    // Where a generated password is written when there is no terminal to show it on.
+    pub password_file: PathBuf,
+}
+
+#[derive(Clone)]
+pub struct MigrationConfig {
+    pub run_on_startup: bool,
+    pub lock_timeout: Duration,
+    // A lock whose holder stopped refreshing it for this long is taken over.
+    pub lock_stale_after: Duration,
+}
+
+#[derive(Clone, Copy, EnumString, Display)]
+pub enum OtpAlphabet {
+    Numeric,
+    Alphanumeric,
+    HumanFriendly,
+}
+
+impl OtpAlphabet {
+    pub fn characters(&self) -> &'static [u8] {
+        match self {
+            OtpAlphabet::Numeric => b"0123456789",
+            OtpAlphabet::Alphanumeric => b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            // Leaves out characters that are easily mistaken for one another, e.g. 0/O and 1/I/L.
+            OtpAlphabet::HumanFriendly => b"23456789ABCDEFGHJKMNPQRSTUVWXYZ",
+        }
+    }
 }
 
 #[derive(EnumString, EnumIs, Display)]
@@ -28,12 +196,21 @@ pub enum AppEnv {
     Prod,
 }
 
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AppConfig {
     pub fn new() -> Self {
         dotenvy::dotenv().expect("Failed to initialise .env");
 
         let host = get_env("HOST");
         let port = get_env("PORT");
+        let public_url = get_env_or("PUBLIC_URL", format!("http://{host}:{port}"))
+            .trim_end_matches('/')
+            .to_string();
 
         let db_url = get_env("DATABASE_URL");
         let show_sql = get_env("SHOW_SQL");
@@ -46,18 +223,193 @@ impl AppConfig {
         let session_refresh_duration = get_env("SESSION_REFRESH_DURATION");
         let session_refresh_duration = Duration::seconds(session_refresh_duration);
 
+        let impersonation_duration =
+            Duration::seconds(get_env_or("IMPERSONATION_SESSION_DURATION", 15 * 60));
+
         let otp_validity_duration = get_env("OTP_VALIDITY_DURATION");
         let otp_validity_duration = Duration::seconds(otp_validity_duration);
+        let otp_max_attempts = get_env_or("OTP_MAX_ATTEMPTS", 5);
+        let otp_length = get_env_or("OTP_LENGTH", 6);
+        let otp_alphabet = get_env_or("OTP_ALPHABET", OtpAlphabet::Numeric);
+
+        assert!(
+            (6..=32).contains(&otp_length),
+            "OTP_LENGTH must be between 6 and 32."
+        );
+
+        let anti_enumeration = get_env_or("ANTI_ENUMERATION", false);
+        let lowercase_email_local_part = get_env_or("EMAIL_LOWERCASE_LOCAL_PART", true);
+
+        let password_policy = PasswordPolicy {
+            min_length: get_env_or("PASSWORD_MIN_LENGTH", 8),
+            max_length: get_env_or("PASSWORD_MAX_LENGTH", 128),
+            require_lowercase: get_env_or("PASSWORD_REQUIRE_LOWERCASE", true),
+            require_uppercase: get_env_or("PASSWORD_REQUIRE_UPPERCASE", true),
+            require_digit: get_env_or("PASSWORD_REQUIRE_DIGIT", true),
+            require_special: get_env_or("PASSWORD_REQUIRE_SPECIAL", true),
+            forbid_email: get_env_or("PASSWORD_FORBID_EMAIL", true),
+            min_strength: get_env_or("PASSWORD_MIN_STRENGTH", 2),
+            breached_passwords_dir: get_optional_env("BREACHED_PASSWORDS_DIR"),
+            history_size: get_env_or("PASSWORD_HISTORY_SIZE", 5),
+        };
+
+        if let Some(dir) = &password_policy.breached_passwords_dir {
+            assert!(
+                dir.is_dir(),
+                "BREACHED_PASSWORDS_DIR {} is not a directory.",
+                dir.display()
+            );
+        }
+
+        let registration_policy = RegistrationPolicy {
+            open: get_env_or("REGISTRATION_OPEN", true),
+            allowed_domains: get_domain_list("REGISTRATION_ALLOWED_DOMAINS"),
+            denied_domains: get_domain_list("REGISTRATION_DENIED_DOMAINS"),
+            block_disposable: get_env_or("REGISTRATION_BLOCK_DISPOSABLE", true),
+        };
+
+        let argon2 = Argon2Config {
+            memory_cost: get_env_or("ARGON2_MEMORY_COST", 19 * 1024),
+            time_cost: get_env_or("ARGON2_TIME_COST", 2),
+            parallelism: get_env_or("ARGON2_PARALLELISM", 1),
+            pepper: get_optional_env("PASSWORD_PEPPER"),
+        };
+
+        let rate_limit = RateLimitConfig {
+            enabled: get_env_or("RATE_LIMIT_ENABLED", true),
+            store: get_env_or("RATE_LIMIT_STORE", RateLimitStoreKind::Memory),
+            trust_forwarded_for: get_env_or("RATE_LIMIT_TRUST_FORWARDED_FOR", false),
+            trusted_proxies: get_env_or("RATE_LIMIT_TRUSTED_PROXIES", 1),
+        };
+
+        assert!(
+            !rate_limit.trust_forwarded_for || rate_limit.trusted_proxies > 0,
+            "RATE_LIMIT_TRUSTED_PROXIES must be at least 1 when trusting X-Forwarded-For."
+        );
+
+        let notification = NotificationConfig {
+            enabled: get_env_or("NOTIFICATIONS_ENABLED", true),
+            mailer: get_env_or("NOTIFICATION_MAILER", MailerKind::Log),
+            smtp_url: get_optional_env("SMTP_URL"),
+            from: get_env_or("NOTIFICATION_FROM", "no-reply@localhost".into()),
+            poll_interval: Duration::seconds(get_env_or("NOTIFICATION_POLL_INTERVAL", 10)),
+            max_attempts: get_env_or("NOTIFICATION_MAX_ATTEMPTS", 5),
+        };
+
+        if notification.mailer.is_smtp() {
+            assert!(
+                notification.smtp_url.is_some(),
+                "SMTP_URL is required when NOTIFICATION_MAILER is Smtp."
+            );
+        }
+
+        // The log mailer writes whole messages, one-time passwords and invitation tokens
+        // included, so it's only meant for local development.
+        assert!(
+            !notification.mailer.is_log() || !is_prod(()),
+            "NOTIFICATION_MAILER must not be Log when ENV is Prod."
+        );
+
+        let invitation = InvitationConfig {
+            secret: get_optional_env("INVITATION_SECRET"),
+            validity_duration: Duration::seconds(get_env_or(
+                "INVITATION_VALIDITY_DURATION",
+                7 * 24 * 60 * 60,
+            )),
+        };
+
+        assert!(
+            invitation
+                .secret
+                .as_ref()
+                .is_none_or(|secret| !secret.is_empty()),
+            "INVITATION_SECRET must not be empty."
+        );
+
+        let storage = StorageConfig {
+            backend: get_env_or("STORAGE_BACKEND", StorageKind::Local),
+            local_dir: get_env_or("STORAGE_LOCAL_DIR", PathBuf::from("storage")),
+        };
+
+        let avatar = AvatarConfig {
+            max_upload_size: get_env_or("AVATAR_MAX_UPLOAD_SIZE", 5 * 1024 * 1024),
+            max_dimension: get_env_or("AVATAR_MAX_DIMENSION", 4096),
+            size: get_env_or("AVATAR_SIZE", 256),
+            thumbnail_size: get_env_or("AVATAR_THUMBNAIL_SIZE", 64),
+        };
+
+        assert!(
+            avatar.thumbnail_size > 0 && avatar.thumbnail_size <= avatar.size,
+            "AVATAR_THUMBNAIL_SIZE must be between 1 and AVATAR_SIZE."
+        );
+
+        let preferences = PreferencesConfig {
+            defaults: PreferencesEntity {
+                theme: get_env_or("PREFERENCE_DEFAULT_THEME", Theme::System),
+                reduced_motion: get_env_or("PREFERENCE_DEFAULT_REDUCED_MOTION", false),
+                email_digest: get_env_or("PREFERENCE_DEFAULT_EMAIL_DIGEST", EmailDigest::Weekly),
+                desktop_notifications: get_env_or("PREFERENCE_DEFAULT_DESKTOP_NOTIFICATIONS", true),
+                beta_features: get_list("PREFERENCE_DEFAULT_BETA_FEATURES"),
+            },
+            beta_features: get_list("BETA_FEATURES"),
+        };
+
+        if let Some(feature) = preferences
+            .defaults
+            .beta_features
+            .iter()
+            .find(|feature| !preferences.beta_features.contains(feature))
+        {
+            panic!("PREFERENCE_DEFAULT_BETA_FEATURES contains {feature}, which is not listed in BETA_FEATURES.");
+        }
+
+        let admin_bootstrap = AdminBootstrapConfig {
+            email: get_optional_env("BOOTSTRAP_ADMIN_EMAIL"),
+            password: get_optional_env("BOOTSTRAP_ADMIN_PASSWORD"),
+            password_file: get_env_or(
+                "BOOTSTRAP_ADMIN_PASSWORD_FILE",
+                PathBuf::from("bootstrap_admin_password"),
+            ),
+        };
+
+        let migrations = MigrationConfig {
+            run_on_startup: get_env_or("MIGRATE_ON_STARTUP", true),
+            lock_timeout: Duration::seconds(get_env_or("MIGRATION_LOCK_TIMEOUT", 60)),
+            lock_stale_after: Duration::seconds(get_env_or("MIGRATION_LOCK_STALE_AFTER", 10 * 60)),
+        };
+
+        assert!(
+            migrations.lock_stale_after >= Duration::seconds(3),
+            "MIGRATION_LOCK_STALE_AFTER must be at least 3 seconds."
+        );
 
         Self {
             host,
             port,
+            public_url,
             db_url,
             show_sql,
             status_code_range_for_error_logging,
             session_duration,
             session_refresh_duration,
+            impersonation_duration,
             otp_validity_duration,
+            otp_max_attempts,
+            otp_length,
+            otp_alphabet,
+            anti_enumeration,
+            lowercase_email_local_part,
+            password_policy,
+            registration_policy,
+            argon2,
+            rate_limit,
+            notification,
+            invitation,
+            storage,
+            avatar,
+            preferences,
+            admin_bootstrap,
+            migrations,
         }
     }
 }
@@ -69,14 +421,13 @@ where
     R::Err: Debug,
 {
     let value: String = value.into();
-    value.parse().expect(
-        format!(
+    value.parse().unwrap_or_else(|_| {
+        panic!(
             "Failed to parse {} into type {}",
             value,
             std::any::type_name::<T>()
         )
-        .as_str(),
-    )
+    })
 }
 
 fn get_env<T>(key: &str) -> T
@@ -84,19 +435,53 @@ where
     T: FromStr,
     T::Err: Debug,
 {
-    let value = env::var(key).expect(format!("Missing {key} in environment.").as_str());
+    let value = env::var(key).unwrap_or_else(|_| panic!("Missing {key} in environment."));
     parse(value)
 }
 
+fn get_env_or<T>(key: &str, default: T) -> T
+where
+    T: FromStr,
+    T::Err: Debug,
+{
+    env::var(key).map(parse).unwrap_or(default)
+}
+
+fn get_optional_env<T>(key: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: Debug,
+{
+    env::var(key).ok().map(parse)
+}
+
+// Comma separated values, e.g. `new-editor, ai-search`.
+fn get_list(key: &str) -> Vec<String> {
+    env::var(key)
+        .unwrap_or_default()
+        .split(',')
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+// Comma separated, case insensitive domains, e.g. `example.com, example.org`.
+fn get_domain_list(key: &str) -> Vec<String> {
+    get_list(key)
+        .into_iter()
+        .map(|domain| domain.to_lowercase())
+        .collect()
+}
+
 fn get_range<T>(key: &str) -> Range<T>
 where
     T: FromStr,
     T::Err: Debug,
 {
     let value = get_env::<String>(key);
-    let (start, end) = value.split_once("..").expect(
-        format!("Wrong format for range. Expected {{start}}..{{end}}, got: {value}").as_str(),
-    );
+    let (start, end) = value.split_once("..").unwrap_or_else(|| {
+        panic!("Wrong format for range. Expected {{start}}..{{end}}, got: {value}")
+    });
 
     let start = parse(start);
     let end = parse(end);
@@ -1,23 +1,25 @@
 pub mod openapi {
-    pub const DEFAULT_SECURITY_SCHEME: &'static str = "Session ID";
+    pub const DEFAULT_SECURITY_SCHEME: &str = "Session ID";
 
     pub mod tags {
-        pub const AUTH_TAG: &'static str = "Auth";
-        pub const FORGOT_PASSWORD_TAG: &'static str = "Forgot Password";
-        pub const USER_TAG: &'static str = "Users";
+        pub const AUTH_TAG: &str = "Auth";
+        pub const FORGOT_PASSWORD_TAG: &str = "Forgot Password";
+        pub const USER_TAG: &str = "Users";
+        pub const NOTIFICATION_TAG: &str = "Notifications";
+        pub const ORGANIZATION_TAG: &str = "Organizations";
+        pub const INVITATION_TAG: &str = "Invitations";
 
         pub mod admin {
-            pub const USER_TAG: &'static str = "Users (Admin)";
+            pub const USER_TAG: &str = "Users (Admin)";
+            pub const AUDIT_TAG: &str = "Audit (Admin)";
+            pub const ROLE_TAG: &str = "Roles (Admin)";
+            pub const INVITATION_TAG: &str = "Invitations (Admin)";
         }
     }
 }
 
 pub mod session {
     pub mod headers {
-        pub const SESSION_HEADER_KEY: &'static str = "X-Session-Id";
+        pub const SESSION_HEADER_KEY: &str = "X-Session-Id";
     }
 }
-
-pub mod admin {
-    pub const ADMIN_EMAIL: &'static str = "admin@localhost";
-}
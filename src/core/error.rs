@@ -1,5 +1,9 @@
 use aide::OperationOutput;
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
 use schemars::JsonSchema;
 use serde::Serialize;
 use thiserror::Error;
@@ -23,8 +27,32 @@ pub enum ApiError {
     #[error("One or more validation errors has occured.")]
     ValidationError(Vec<crate::core::validators::ValidationError>),
 
+    #[error("Too many requests. Please try again later.")]
+    RateLimited(u64),
+
+    #[error(transparent)]
+    MultipartRejection(#[from] axum::extract::multipart::MultipartRejection),
+
+    #[error(transparent)]
+    MultipartError(#[from] axum::extract::multipart::MultipartError),
+
+    #[error("An unknown error has occured.")]
+    StorageError(#[from] crate::core::storage::StorageError),
+
+    #[error("An unknown error has occured.")]
+    BreachedPasswordsError(std::io::Error),
+
+    #[error("Failed to read the request body.")]
+    RequestBodyError(axum::Error),
+
     #[error(transparent)]
     AccountError(#[from] crate::account::error::AccountError),
+
+    #[error(transparent)]
+    OrganizationError(#[from] crate::organization::error::OrganizationError),
+
+    #[error(transparent)]
+    InvitationError(#[from] crate::invitation::error::InvitationError),
 }
 
 pub trait ErrorCode {
@@ -51,14 +79,22 @@ impl ErrorCode for ApiError {
             ApiError::HeaderToStrError(_) => "GBL0001",
             ApiError::JsonDeserializeError(_) => "GBL0002",
             ApiError::ValidationError(_) => "GBL0003",
+            ApiError::RateLimited(_) => "GBL0004",
+            ApiError::MultipartRejection(_) => "GBL0005",
+            ApiError::MultipartError(_) => "GBL0006",
+            ApiError::StorageError(_) => "GBL9997",
+            ApiError::BreachedPasswordsError(_) => "GBL9996",
+            ApiError::RequestBodyError(_) => "GBL0007",
             ApiError::AccountError(error) => error.code(),
+            ApiError::OrganizationError(error) => error.code(),
+            ApiError::InvitationError(error) => error.code(),
         }
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> axum::response::Response {
-        match &self {
+        let mut response = match &self {
             ApiError::SqlxError(error) => ApiErrorResponse {
                 status_code: StatusCode::INTERNAL_SERVER_ERROR,
                 code: self.code().into(),
@@ -94,9 +130,67 @@ impl IntoResponse for ApiError {
                 debug_description: None,
                 validation_errors: AppValidationErrorResponse::from_errors(errors),
             },
+            ApiError::RateLimited(retry_after) => ApiErrorResponse {
+                status_code: StatusCode::TOO_MANY_REQUESTS,
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: Some(format!("Retry after {retry_after} seconds.")),
+                validation_errors: vec![],
+            },
+            ApiError::MultipartRejection(error) => ApiErrorResponse {
+                status_code: error.status(),
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: Some(error.to_string()),
+                validation_errors: vec![],
+            },
+            ApiError::MultipartError(error) => ApiErrorResponse {
+                status_code: error.status(),
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: Some(error.to_string()),
+                validation_errors: vec![],
+            },
+            ApiError::StorageError(error) => ApiErrorResponse {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: Some(error.to_string()),
+                validation_errors: vec![],
+            },
+            ApiError::BreachedPasswordsError(error) => ApiErrorResponse {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: Some(error.to_string()),
+                validation_errors: vec![],
+            },
+            ApiError::RequestBodyError(error) => ApiErrorResponse {
+                status_code: if std::error::Error::source(error)
+                    .is_some_and(|source| source.is::<http_body_util::LengthLimitError>())
+                {
+                    StatusCode::PAYLOAD_TOO_LARGE
+                } else {
+                    StatusCode::BAD_REQUEST
+                },
+                code: self.code().into(),
+                message: self.to_string(),
+                debug_description: Some(error.to_string()),
+                validation_errors: vec![],
+            },
             ApiError::AccountError(error) => error.into_app_error_response(),
+            ApiError::OrganizationError(error) => error.into_app_error_response(),
+            ApiError::InvitationError(error) => error.into_app_error_response(),
         }
-        .into_response()
+        .into_response();
+
+        if let ApiError::RateLimited(retry_after) = &self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(*retry_after));
+        }
+
+        response
     }
 }
 
@@ -105,6 +199,7 @@ impl OperationOutput for ApiError {
 }
 
 pub trait IntoApiErrorResponse {
+    #[allow(clippy::wrong_self_convention)]
     fn into_app_error_response(&self) -> ApiErrorResponse;
 }
 
@@ -125,6 +220,8 @@ pub struct ApiErrorResponse {
 
 #[derive(Serialize, JsonSchema)]
 pub struct AppValidationErrorResponse {
+    /// JSON pointer to the invalid value relative to the request body, without the leading
+    /// slash, e.g. `addresses/0/postcode`.
     pub property: String,
     pub errors: Vec<String>,
 }
@@ -139,7 +236,7 @@ impl From<crate::core::validators::ValidationError> for AppValidationErrorRespon
 }
 
 impl AppValidationErrorResponse {
-    pub fn from_errors(errors: &Vec<crate::core::validators::ValidationError>) -> Vec<Self> {
+    pub fn from_errors(errors: &[crate::core::validators::ValidationError]) -> Vec<Self> {
         errors.iter().map(|error| error.to_owned().into()).collect()
     }
 }
@@ -1,9 +1,16 @@
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
 use aide::{OperationInput, OperationOutput};
-use axum::{extract::FromRequest, response::IntoResponse, Json};
+use axum::{
+    extract::{ConnectInfo, FromRef, FromRequest, FromRequestParts, Multipart},
+    http::{header::USER_AGENT, request::Parts},
+    response::IntoResponse,
+    Json,
+};
 use schemars::JsonSchema;
 use serde::{de::DeserializeOwned, Serialize};
 
-use super::{error::ApiError, validators::Validatable};
+use super::{error::ApiError, validators::Validatable, AppConfig, RateLimitConfig};
 
 pub struct JsonRequest<T>(pub T);
 
@@ -40,15 +47,17 @@ impl<T, S> FromRequest<S> for ValidJsonRequest<T>
 where
     T: Validatable + DeserializeOwned,
     S: Send + Sync,
+    Arc<AppConfig>: FromRef<S>,
 {
     type Rejection = ApiError;
 
     async fn from_request(req: axum::extract::Request, state: &S) -> Result<Self, Self::Rejection> {
+        let config = Arc::<AppConfig>::from_ref(state);
         let json = <Json<T> as FromRequest<S>>::from_request(req, state)
             .await
             .map(|json| json.0)
             .map_err(ApiError::from)?;
-        json.validate().map_err(ApiError::from)?;
+        json.validate(&config).map_err(ApiError::from)?;
 
         Ok(ValidJsonRequest(json))
     }
@@ -66,6 +75,31 @@ where
     }
 }
 
+pub struct MultipartRequest(pub Multipart);
+
+impl<S> FromRequest<S> for MultipartRequest
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: axum::extract::Request, state: &S) -> Result<Self, Self::Rejection> {
+        <Multipart as FromRequest<S>>::from_request(req, state)
+            .await
+            .map(MultipartRequest)
+            .map_err(ApiError::from)
+    }
+}
+
+impl OperationInput for MultipartRequest {
+    fn operation_input(
+        ctx: &mut aide::generate::GenContext,
+        operation: &mut aide::openapi::Operation,
+    ) {
+        <Multipart as OperationInput>::operation_input(ctx, operation);
+    }
+}
+
 pub struct JsonResponse<T>(pub T);
 
 impl<T> IntoResponse for JsonResponse<T>
@@ -97,3 +131,67 @@ where
         <Json<T> as OperationOutput>::inferred_responses(ctx, operation)
     }
 }
+
+pub struct ClientIp(pub Option<String>);
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+    Arc<AppConfig>: FromRef<S>,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let config = Arc::<AppConfig>::from_ref(state);
+        Ok(ClientIp(client_ip(parts, &config.rate_limit)))
+    }
+}
+
+impl OperationInput for ClientIp {}
+
+// Each proxy appends the address it received the request from, so only the entries added by our
+// own proxies can be trusted. Anything further left is whatever the client chose to send.
+pub fn client_ip(parts: &Parts, config: &RateLimitConfig) -> Option<String> {
+    let forwarded_for = parts
+        .headers
+        .get_all("X-Forwarded-For")
+        .iter()
+        .filter(|_| config.trust_forwarded_for)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect::<Vec<&str>>()
+        .into_iter()
+        .rev()
+        .nth(config.trusted_proxies.saturating_sub(1))
+        .filter(|ip| !ip.is_empty())
+        .map(str::to_string);
+
+    forwarded_for.or_else(|| {
+        parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip().to_string())
+    })
+}
+
+pub struct UserAgent(pub Option<String>);
+
+impl<S> FromRequestParts<S> for UserAgent
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(UserAgent(
+            parts
+                .headers
+                .get(USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+        ))
+    }
+}
+
+impl OperationInput for UserAgent {}
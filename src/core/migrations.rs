@@ -0,0 +1,265 @@
+use std::{collections::HashMap, time::Instant};
+
+use chrono::{Duration, Utc};
+use sqlx::{
+    migrate::{Migrate, MigrateDatabase, MigrateError, Migrator},
+    Sqlite, SqlitePool,
+};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::MigrationConfig;
+
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+const LOCK_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error(transparent)]
+    Migrate(#[from] MigrateError),
+
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error(
+        "Timed out after {}s waiting for another process to finish migrating the database.",
+        .0.num_seconds()
+    )]
+    LockTimeout(Duration),
+}
+
+pub enum MigrationState {
+    Applied,
+    Pending,
+    ChecksumMismatch,
+    // Applied by a newer version of the app, so this binary does not know about it.
+    Unknown,
+}
+
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+pub async fn create_database_if_missing(db_url: &str) -> Result<(), sqlx::Error> {
+    if !Sqlite::database_exists(db_url).await? {
+        tracing::info!("Creating database at {db_url}");
+        Sqlite::create_database(db_url).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn run(pool: &SqlitePool, config: &MigrationConfig) -> Result<(), MigrationError> {
+    let lock = MigrationLock::acquire(pool, config).await?;
+    let result = MIGRATOR.run(pool).await;
+    lock.release().await?;
+
+    result.map_err(MigrationError::from)
+}
+
+// Reverts the latest applied migration, or every migration newer than `target` when given.
+pub async fn undo(
+    pool: &SqlitePool,
+    target: Option<i64>,
+    config: &MigrationConfig,
+) -> Result<Option<i64>, MigrationError> {
+    let lock = MigrationLock::acquire(pool, config).await?;
+    let result = undo_locked(pool, target).await;
+    lock.release().await?;
+
+    result
+}
+
+async fn undo_locked(
+    pool: &SqlitePool,
+    target: Option<i64>,
+) -> Result<Option<i64>, MigrationError> {
+    let mut applied = applied_versions(pool).await?;
+    applied.sort();
+
+    let target = match target {
+        Some(target) => target,
+        None if applied.is_empty() => return Ok(None),
+        None => applied.iter().rev().nth(1).copied().unwrap_or(0),
+    };
+
+    MIGRATOR.undo(pool, target).await?;
+    Ok(Some(target))
+}
+
+// Read-only, a database that was never migrated simply has every migration pending.
+pub async fn status(pool: &SqlitePool) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let mut connection = pool.acquire().await?;
+    let has_migrations_table = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(&mut *connection)
+    .await?;
+    let mut applied = if has_migrations_table {
+        connection
+            .list_applied_migrations()
+            .await?
+            .into_iter()
+            .map(|migration| (migration.version, migration.checksum))
+            .collect::<HashMap<_, _>>()
+    } else {
+        HashMap::new()
+    };
+
+    let mut statuses = MIGRATOR
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| {
+            let state = match applied.remove(&migration.version) {
+                Some(checksum) if checksum == migration.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::ChecksumMismatch,
+                None => MigrationState::Pending,
+            };
+
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                state,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    statuses.extend(applied.into_keys().map(|version| MigrationStatus {
+        version,
+        description: String::new(),
+        state: MigrationState::Unknown,
+    }));
+    statuses.sort_by_key(|status| status.version);
+
+    Ok(statuses)
+}
+
+async fn applied_versions(pool: &SqlitePool) -> Result<Vec<i64>, MigrateError> {
+    let mut connection = pool.acquire().await?;
+    connection.ensure_migrations_table().await?;
+
+    Ok(connection
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| migration.version)
+        .collect())
+}
+
+// SQLite has no advisory locks and sqlx does not lock SQLite databases while migrating, so
+// concurrent runners coordinate through a single row table instead. The holder refreshes the lock
+// while it runs, so only the lock of a runner that crashed mid-way goes stale and is taken over.
+struct MigrationLock<'a> {
+    pool: &'a SqlitePool,
+    owner: String,
+    heartbeat: JoinHandle<()>,
+}
+
+impl<'a> MigrationLock<'a> {
+    async fn acquire(
+        pool: &'a SqlitePool,
+        config: &MigrationConfig,
+    ) -> Result<Self, MigrationError> {
+        let timeout = config.lock_timeout;
+        sqlx::query(
+            "
+            CREATE TABLE IF NOT EXISTS _migrations_lock (
+                id INTEGER NOT NULL PRIMARY KEY CHECK (id = 1),
+                owner TEXT NOT NULL,
+                acquired_at INTEGER NOT NULL
+            )
+            ",
+        )
+        .execute(pool)
+        .await?;
+
+        let owner = Uuid::new_v4().to_string();
+        let started_at = Instant::now();
+        let mut is_waiting = false;
+
+        loop {
+            let now = Utc::now().timestamp();
+            let acquired = sqlx::query(
+                "
+                INSERT INTO _migrations_lock (id, owner, acquired_at)
+                VALUES (1, ?, ?)
+                ON CONFLICT (id) DO UPDATE SET
+                    owner = excluded.owner,
+                    acquired_at = excluded.acquired_at
+                WHERE acquired_at < ?
+                ",
+            )
+            .bind(&owner)
+            .bind(now)
+            .bind(now - config.lock_stale_after.num_seconds())
+            .execute(pool)
+            .await?
+            .rows_affected()
+                == 1;
+
+            if acquired {
+                let heartbeat = spawn_heartbeat(pool.clone(), owner.clone(), config);
+                return Ok(Self {
+                    pool,
+                    owner,
+                    heartbeat,
+                });
+            }
+
+            if started_at.elapsed() >= timeout.to_std().unwrap_or_default() {
+                return Err(MigrationError::LockTimeout(timeout));
+            }
+
+            if !is_waiting {
+                tracing::info!("Waiting for another process to finish migrating the database");
+                is_waiting = true;
+            }
+            tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn release(self) -> Result<(), MigrationError> {
+        self.heartbeat.abort();
+        sqlx::query("DELETE FROM _migrations_lock WHERE owner = ?")
+            .bind(&self.owner)
+            .execute(self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+// A failed refresh is only logged, the next one may succeed before the lock goes stale.
+fn spawn_heartbeat(pool: SqlitePool, owner: String, config: &MigrationConfig) -> JoinHandle<()> {
+    let period = (config.lock_stale_after / 3)
+        .to_std()
+        .unwrap_or(std::time::Duration::from_secs(1));
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            let result = sqlx::query("UPDATE _migrations_lock SET acquired_at = ? WHERE owner = ?")
+                .bind(Utc::now().timestamp())
+                .bind(&owner)
+                .execute(&pool)
+                .await;
+
+            match result {
+                Ok(result) if result.rows_affected() == 0 => {
+                    tracing::warn!("The migration lock was taken over by another process");
+                    return;
+                }
+                Ok(_) => {}
+                Err(err) => tracing::warn!("Failed to refresh the migration lock: {err}"),
+            }
+        }
+    })
+}
@@ -1,10 +1,14 @@
 mod app;
+pub mod cli;
 mod config;
 pub mod constants;
 pub mod error;
 pub mod extractors;
+pub mod migrations;
 pub mod models;
+pub mod rate_limit;
 mod state;
+pub mod storage;
 pub mod types;
 pub mod utils;
 pub mod validators;
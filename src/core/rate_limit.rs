@@ -0,0 +1,306 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use lru::LruCache;
+use sqlx::SqlitePool;
+use strum::{Display, EnumString};
+
+use crate::{
+    account::{database::user_repository, utils::normalize_email},
+    core::{
+        constants::session::headers::SESSION_HEADER_KEY,
+        error::{ApiError, ApiResult},
+        extractors::client_ip,
+        AppState,
+    },
+};
+
+const MAX_BUFFERED_BODY_SIZE: usize = 2 * 1024 * 1024;
+// Once full, new keys are refused until the least recently used bucket has gone stale.
+const MAX_IN_MEMORY_BUCKETS: NonZeroUsize = NonZeroUsize::new(10_000).unwrap();
+// Every policy fully refills well within this, so older buckets are no different from new ones.
+const STALE_BUCKET_SECONDS: i64 = 60 * 60;
+const PRUNE_INTERVAL_SECONDS: i64 = 60;
+
+#[derive(Clone, Copy, EnumString, Display)]
+pub enum RateLimitStoreKind {
+    Memory,
+    Sqlite,
+}
+
+#[derive(Clone, Copy)]
+pub enum RateLimitKey {
+    Ip,
+    // The account named by the `identifier` or `email` of the JSON body, however it is spelled.
+    Account,
+    UserId,
+}
+
+#[derive(Clone, Copy)]
+pub struct RateLimitPolicy {
+    pub name: &'static str,
+    pub key: RateLimitKey,
+    pub capacity: u32,
+    pub refill_interval: Duration,
+}
+
+impl RateLimitPolicy {
+    pub const fn new(
+        name: &'static str,
+        key: RateLimitKey,
+        capacity: u32,
+        refill_interval: Duration,
+    ) -> Self {
+        Self {
+            name,
+            key,
+            capacity,
+            refill_interval,
+        }
+    }
+
+    fn refill_rate(&self) -> f64 {
+        1.0 / self.refill_interval.as_secs_f64()
+    }
+
+    fn retry_after(&self, tokens: f64) -> u64 {
+        ((1.0 - tokens) / self.refill_rate()).ceil().max(1.0) as u64
+    }
+}
+
+#[derive(Clone)]
+pub struct TokenBucket {
+    tokens: f64,
+    refilled_at: f64,
+}
+
+#[derive(Clone)]
+pub enum RateLimitStore {
+    Memory(Arc<Mutex<LruCache<String, TokenBucket>>>),
+    Sqlite(SqlitePool, Arc<AtomicI64>),
+}
+
+impl RateLimitStore {
+    pub fn new(kind: RateLimitStoreKind, pool: SqlitePool) -> Self {
+        match kind {
+            RateLimitStoreKind::Memory => {
+                Self::Memory(Arc::new(Mutex::new(LruCache::new(MAX_IN_MEMORY_BUCKETS))))
+            }
+            RateLimitStoreKind::Sqlite => Self::Sqlite(pool, Default::default()),
+        }
+    }
+
+    async fn take(&self, key: &str, policy: &RateLimitPolicy) -> ApiResult<Option<u64>> {
+        let now = Utc::now().timestamp_millis() as f64 / 1000.0;
+        let capacity = policy.capacity as f64;
+        let rate = policy.refill_rate();
+
+        let (tokens, allowed) = match self {
+            RateLimitStore::Memory(buckets) => {
+                let mut buckets = buckets.lock().unwrap();
+                if !buckets.contains(key) && buckets.len() == buckets.cap().get() {
+                    // Evicting a live bucket would let its key start over with a full one, so
+                    // only a bucket that has had time to refill completely makes room.
+                    let idle = buckets
+                        .peek_lru()
+                        .map_or(0.0, |(_, bucket)| now - bucket.refilled_at);
+                    if idle < STALE_BUCKET_SECONDS as f64 {
+                        return Ok(Some((STALE_BUCKET_SECONDS as f64 - idle).ceil() as u64));
+                    }
+                    buckets.pop_lru();
+                }
+
+                let bucket = buckets.get_or_insert_mut(key.to_string(), || TokenBucket {
+                    tokens: capacity,
+                    refilled_at: now,
+                });
+                let tokens = capacity.min(bucket.tokens + (now - bucket.refilled_at) * rate);
+                let allowed = tokens >= 1.0;
+
+                bucket.tokens = if allowed { tokens - 1.0 } else { tokens };
+                bucket.refilled_at = now;
+
+                (bucket.tokens, allowed)
+            }
+            RateLimitStore::Sqlite(pool, pruned_at) => {
+                prune_stale_buckets(pool, pruned_at).await?;
+
+                let initial_tokens = capacity - 1.0;
+                sqlx::query!(
+                    "
+                    INSERT INTO rate_limit_buckets (key, tokens, allowed, refilled_at)
+                    VALUES (?, ?, 1, ?)
+                    ON CONFLICT (key) DO UPDATE SET
+                        tokens = MIN(?, tokens + (excluded.refilled_at - refilled_at) * ?)
+                            - (MIN(?, tokens + (excluded.refilled_at - refilled_at) * ?) >= 1),
+                        allowed = MIN(?, tokens + (excluded.refilled_at - refilled_at) * ?) >= 1,
+                        refilled_at = excluded.refilled_at
+                    RETURNING tokens, allowed
+                    ",
+                    key,
+                    initial_tokens,
+                    now,
+                    capacity,
+                    rate,
+                    capacity,
+                    rate,
+                    capacity,
+                    rate,
+                )
+                .fetch_one(pool)
+                .await
+                .map(|result| (result.tokens, result.allowed != 0))
+                .map_err(ApiError::from)?
+            }
+        };
+
+        if allowed {
+            Ok(None)
+        } else {
+            Ok(Some(policy.retry_after(tokens)))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimiter {
+    state: AppState,
+    policies: Vec<RateLimitPolicy>,
+}
+
+impl RateLimiter {
+    pub fn new(state: &AppState, policies: &[RateLimitPolicy]) -> Self {
+        Self {
+            state: state.clone(),
+            policies: policies.to_vec(),
+        }
+    }
+}
+
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> ApiResult<Response> {
+    let config = &limiter.state.config.rate_limit;
+    if !config.enabled {
+        return Ok(next.run(request).await);
+    }
+
+    let (parts, body) = request.into_parts();
+    let needs_body = limiter
+        .policies
+        .iter()
+        .any(|policy| matches!(policy.key, RateLimitKey::Account));
+    let (body, json) = if needs_body {
+        let bytes = axum::body::to_bytes(body, MAX_BUFFERED_BODY_SIZE)
+            .await
+            .map_err(ApiError::RequestBodyError)?;
+        let json = serde_json::from_slice::<serde_json::Value>(&bytes).ok();
+
+        (Body::from(bytes), json)
+    } else {
+        (body, None)
+    };
+
+    for policy in &limiter.policies {
+        let key = match policy.key {
+            RateLimitKey::Ip => client_ip(&parts, config),
+            RateLimitKey::Account => match json
+                .as_ref()
+                .and_then(|json| json.get("identifier").or(json.get("email"))?.as_str())
+            {
+                Some(identifier) => Some(account_key(&limiter.state, identifier).await?),
+                None => None,
+            },
+            RateLimitKey::UserId => session_user_id(&limiter.state, &parts)
+                .await?
+                .map(|id| id.to_string()),
+        };
+
+        let Some(key) = key else {
+            continue;
+        };
+
+        let key = format!("{}:{key}", policy.name);
+        if let Some(retry_after) = limiter.state.rate_limit_store.take(&key, policy).await? {
+            tracing::warn!("Rate limit {key} exceeded, retry after {retry_after}s");
+            return Err(ApiError::RateLimited(retry_after));
+        }
+    }
+
+    Ok(next.run(Request::from_parts(parts, body)).await)
+}
+
+// Runs at most once per interval, whichever request gets there first does the work.
+async fn prune_stale_buckets(pool: &SqlitePool, pruned_at: &AtomicI64) -> ApiResult<()> {
+    let now = Utc::now().timestamp();
+    let last_pruned_at = pruned_at.load(Ordering::Relaxed);
+    if now - last_pruned_at < PRUNE_INTERVAL_SECONDS
+        || pruned_at
+            .compare_exchange(last_pruned_at, now, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+    {
+        return Ok(());
+    }
+
+    let stale_before = (now - STALE_BUCKET_SECONDS) as f64;
+    sqlx::query!(
+        "DELETE FROM rate_limit_buckets WHERE refilled_at < ?",
+        stale_before
+    )
+    .execute(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(())
+}
+
+// Login accepts either an email or a username as `identifier`. Both resolve to the same bucket
+// when the account exists, otherwise the identifier is normalized the way the handlers do.
+async fn account_key(state: &AppState, identifier: &str) -> ApiResult<String> {
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+    let (user, key) = if identifier.contains('@') {
+        let email = normalize_email(&state.config, identifier);
+        let user = user_repository::find_user_by_email(&mut connection, &email).await?;
+        (user, format!("email:{email}"))
+    } else {
+        let username = identifier.trim();
+        let user = user_repository::find_user_by_username(&mut connection, username).await?;
+        (user, format!("username:{username}"))
+    };
+
+    Ok(user.map_or(key, |user| format!("user:{}", user.id)))
+}
+
+async fn session_user_id(
+    state: &AppState,
+    parts: &axum::http::request::Parts,
+) -> ApiResult<Option<i64>> {
+    let Some(token) = parts
+        .headers
+        .get(SESSION_HEADER_KEY)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return Ok(None);
+    };
+
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+    user_repository::find_user_by_token(&mut connection, token)
+        .await
+        .map(|user| user.map(|user| user.id))
+}
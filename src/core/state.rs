@@ -1,34 +1,67 @@
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
+use axum::extract::FromRef;
 use sqlx::{
     sqlite::{SqliteConnectOptions, SqlitePoolOptions},
     ConnectOptions, SqlitePool,
 };
 
-use super::AppConfig;
+use super::{rate_limit::RateLimitStore, storage::FileStorage, AppConfig};
 
 #[derive(Clone)]
 pub struct AppState {
-    pub config: AppConfig,
+    pub config: Arc<AppConfig>,
     pub pool: SqlitePool,
+    pub rate_limit_store: RateLimitStore,
+    pub storage: FileStorage,
 }
 
 impl AppState {
     pub async fn new(config: AppConfig) -> Self {
-        let db_url = config.clone().db_url;
-        let mut options = SqliteConnectOptions::from_str(&db_url)
-            .unwrap()
-            .log_statements(tracing::log::LevelFilter::Debug);
+        let db_url = config.db_url.clone();
+        let pool = SqlitePoolOptions::new()
+            .connect_with(connect_options(&config))
+            .await
+            .unwrap_or_else(|_| panic!("Failed to connect to database at {db_url}"));
+
+        Self::with_pool(config, pool)
+    }
+
+    // Connects on first use, for commands such as `openapi export` that never query the database.
+    pub fn without_connecting(config: AppConfig) -> Self {
+        let pool = SqlitePoolOptions::new().connect_lazy_with(connect_options(&config));
+
+        Self::with_pool(config, pool)
+    }
+
+    fn with_pool(config: AppConfig, pool: SqlitePool) -> Self {
+        let rate_limit_store = RateLimitStore::new(config.rate_limit.store, pool.clone());
 
-        if !config.show_sql {
-            options = options.disable_statement_logging();
+        let storage = FileStorage::new(&config.storage);
+
+        AppState {
+            config: Arc::new(config),
+            pool,
+            rate_limit_store,
+            storage,
         }
+    }
+}
 
-        let pool = SqlitePoolOptions::new()
-            .connect_with(options)
-            .await
-            .expect(format!("Failed to connect to database at {db_url}").as_str());
+fn connect_options(config: &AppConfig) -> SqliteConnectOptions {
+    let options = SqliteConnectOptions::from_str(&config.db_url)
+        .unwrap()
+        .log_statements(tracing::log::LevelFilter::Debug);
+
+    if config.show_sql {
+        options
+    } else {
+        options.disable_statement_logging()
+    }
+}
 
-        AppState { config, pool }
+impl FromRef<AppState> for Arc<AppConfig> {
+    fn from_ref(input: &AppState) -> Self {
+        input.config.clone()
     }
 }
@@ -0,0 +1,254 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    io::ErrorKind,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use axum::body::Bytes;
+use strum::{Display, EnumString};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::StorageConfig;
+
+#[derive(Clone, Copy, EnumString, Display)]
+pub enum StorageKind {
+    Local,
+    Memory,
+}
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Invalid storage key {0}.")]
+    InvalidKey(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+// Keys are `/` separated paths, e.g. `avatars/1/{hash}.png`. Backends must not let a key escape
+// their root, so every key goes through `validate_key` first.
+pub trait Storage {
+    fn put(&self, key: &str, bytes: Bytes)
+        -> impl Future<Output = Result<(), StorageError>> + Send;
+
+    fn get(&self, key: &str) -> impl Future<Output = Result<Option<Bytes>, StorageError>> + Send;
+
+    fn exists(&self, key: &str) -> impl Future<Output = Result<bool, StorageError>> + Send;
+
+    fn delete(&self, key: &str) -> impl Future<Output = Result<(), StorageError>> + Send;
+}
+
+#[derive(Clone)]
+pub enum FileStorage {
+    Local(LocalStorage),
+    Memory(MemoryStorage),
+}
+
+impl FileStorage {
+    pub fn new(config: &StorageConfig) -> Self {
+        match config.backend {
+            StorageKind::Local => Self::Local(LocalStorage::new(config.local_dir.clone())),
+            StorageKind::Memory => Self::Memory(MemoryStorage::default()),
+        }
+    }
+}
+
+impl Storage for FileStorage {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), StorageError> {
+        match self {
+            FileStorage::Local(storage) => storage.put(key, bytes).await,
+            FileStorage::Memory(storage) => storage.put(key, bytes).await,
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, StorageError> {
+        match self {
+            FileStorage::Local(storage) => storage.get(key).await,
+            FileStorage::Memory(storage) => storage.get(key).await,
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        match self {
+            FileStorage::Local(storage) => storage.exists(key).await,
+            FileStorage::Memory(storage) => storage.exists(key).await,
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match self {
+            FileStorage::Local(storage) => storage.delete(key).await,
+            FileStorage::Memory(storage) => storage.delete(key).await,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Write to a temporary file first so readers never see a partially written file.
+        let temporary_path = path.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
+        tokio::fs::write(&temporary_path, &bytes).await?;
+        if let Err(err) = tokio::fs::rename(&temporary_path, &path).await {
+            let _ = tokio::fs::remove_file(&temporary_path).await;
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, StorageError> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(bytes) => Ok(Some(bytes.into())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        Ok(tokio::fs::try_exists(self.path(key)?).await?)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    objects: Arc<RwLock<HashMap<String, Bytes>>>,
+}
+
+impl Storage for MemoryStorage {
+    async fn put(&self, key: &str, bytes: Bytes) -> Result<(), StorageError> {
+        validate_key(key)?;
+        self.objects.write().unwrap().insert(key.to_string(), bytes);
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>, StorageError> {
+        validate_key(key)?;
+        Ok(self.objects.read().unwrap().get(key).cloned())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        validate_key(key)?;
+        Ok(self.objects.read().unwrap().contains_key(key))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        validate_key(key)?;
+        self.objects.write().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+fn validate_key(key: &str) -> Result<(), StorageError> {
+    let is_valid = key.split('/').all(|segment| {
+        !segment.is_empty()
+            && segment != "."
+            && segment != ".."
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    });
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_key_accepts_nested_keys() {
+        assert!(validate_key("avatars/1/0123abcd.png").is_ok());
+        assert!(validate_key("avatars/1/0123abcd-thumb.png").is_ok());
+    }
+
+    #[test]
+    fn validate_key_rejects_traversal() {
+        for key in [
+            "..",
+            "../secret",
+            "avatars/../../etc/passwd",
+            "avatars/./1.png",
+            "/etc/passwd",
+            "avatars//1.png",
+            "avatars/1.png/",
+            "avatars\\..\\1.png",
+            "avatars/%2e%2e/1.png",
+            "",
+        ] {
+            assert!(
+                matches!(validate_key(key), Err(StorageError::InvalidKey(_))),
+                "{key} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn memory_storage_round_trip() {
+        let storage = MemoryStorage::default();
+        let key = "avatars/1/image.png";
+
+        assert!(storage.get(key).await.unwrap().is_none());
+        assert!(!storage.exists(key).await.unwrap());
+
+        storage
+            .put(key, Bytes::from_static(b"image"))
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get(key).await.unwrap(),
+            Some(Bytes::from_static(b"image"))
+        );
+        assert!(storage.exists(key).await.unwrap());
+
+        storage.delete(key).await.unwrap();
+        assert!(storage.get(key).await.unwrap().is_none());
+        assert!(!storage.exists(key).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn memory_storage_rejects_invalid_keys() {
+        let storage = MemoryStorage::default();
+
+        assert!(storage
+            .put("../image.png", Bytes::from_static(b"image"))
+            .await
+            .is_err());
+        assert!(storage.get("../image.png").await.is_err());
+    }
+}
@@ -13,9 +13,9 @@ impl DbDateTime {
     }
 }
 
-impl Into<DateTime<Utc>> for DbDateTime {
-    fn into(self) -> DateTime<Utc> {
-        self.0
+impl From<DbDateTime> for DateTime<Utc> {
+    fn from(value: DbDateTime) -> Self {
+        value.0
     }
 }
 
@@ -35,6 +35,10 @@ impl Type<Sqlite> for DbDateTime {
     fn type_info() -> <Sqlite as sqlx::Database>::TypeInfo {
         <DateTime<Utc> as Type<Sqlite>>::type_info()
     }
+
+    fn compatible(ty: &<Sqlite as sqlx::Database>::TypeInfo) -> bool {
+        <DateTime<Utc> as Type<Sqlite>>::compatible(ty)
+    }
 }
 
 impl Encode<'_, Sqlite> for DbDateTime {
@@ -45,7 +49,7 @@ impl Encode<'_, Sqlite> for DbDateTime {
         let datetime = self.0.format("%F %T.%f").to_string();
         let (datetime, microseconds) = datetime.split_once(".").unwrap();
         let nanoseconds = microseconds.to_string().parse::<u32>().unwrap();
-        let milliseconds = nanoseconds / 1000_000;
+        let milliseconds = nanoseconds / 1_000_000;
         let datetime = format!("{datetime}.{milliseconds}");
 
         Encode::<Sqlite>::encode(datetime, buf)
@@ -1,5 +1,7 @@
 use std::env;
 
+use serde::{Deserialize, Deserializer};
+
 use super::AppEnv;
 
 pub fn is_prod<T>(_: T) -> bool {
@@ -13,3 +15,13 @@ pub fn is_prod<T>(_: T) -> bool {
 pub fn should_add_openapi_routes() -> bool {
     !is_prod(())
 }
+
+// Used with `#[serde(default)]` on `Option<Option<T>>` fields to tell a missing field (`None`)
+// apart from an explicit `null` (`Some(None)`), as required by JSON merge patch.
+pub fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
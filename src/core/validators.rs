@@ -1,12 +1,17 @@
-use std::sync::LazyLock;
+use std::{collections::HashSet, fs, io, path::Path, sync::OnceLock};
 
 use email_address::EmailAddress;
-use fancy_regex::Regex;
+use sha1::{Digest, Sha1};
 
-const PASSWORD_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^(?=.*[a-z])(?=.*[A-Z])(?=.*\d)(?=.*[@$!%*?&])[A-Za-z\d@$!%*?&]{8,12}$").unwrap()
-});
-const INVALID_PASSWORD_MESSAGE: &'static str = "Password must contain a minimum of 8-12 characters, with at least 1 uppercase letter, 1 lowercase letter, 1 number and 1 special character.";
+use super::{
+    error::{ApiError, ApiResult},
+    AppConfig, PasswordPolicy, RegistrationPolicy,
+};
+
+const COMMON_PASSWORD_FRAGMENTS: [&str; 12] = [
+    "password", "qwerty", "letmein", "welcome", "admin", "login", "monkey", "dragon", "iloveyou",
+    "abc123", "111111", "123456",
+];
 
 #[derive(Clone, Debug)]
 pub struct ValidationError {
@@ -14,21 +19,48 @@ pub struct ValidationError {
     pub errors: Vec<String>,
 }
 
+impl ValidationError {
+    pub fn new(property: &str, errors: Vec<String>) -> Self {
+        Self {
+            property: escape_pointer_segment(property),
+            errors,
+        }
+    }
+
+    pub fn nested_in(self, parent: &str) -> Self {
+        Self {
+            property: format!("{}/{}", escape_pointer_segment(parent), self.property),
+            errors: self.errors,
+        }
+    }
+}
+
 pub trait Validatable {
     fn validated_properties() -> Vec<String>;
 
-    fn validate_property(&self, property: &str) -> Option<Vec<String>>;
+    fn validate_property(&self, property: &str, config: &AppConfig) -> Option<Vec<String>>;
+
+    fn validate_nested_property(
+        &self,
+        _property: &str,
+        _config: &AppConfig,
+    ) -> Vec<ValidationError> {
+        vec![]
+    }
 
-    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+    fn validate(&self, config: &AppConfig) -> Result<(), Vec<ValidationError>> {
         let errors = Self::validated_properties()
             .iter()
-            .filter_map(|property| {
-                self.validate_property(property)
-                    .map(|errors| (property, errors))
-            })
-            .map(|(property, errors)| ValidationError {
-                property: property.to_owned(),
-                errors,
+            .flat_map(|property| {
+                let errors = self
+                    .validate_property(property, config)
+                    .map(|errors| ValidationError::new(property, errors));
+                let nested_errors = self
+                    .validate_nested_property(property, config)
+                    .into_iter()
+                    .map(|error| error.nested_in(property));
+
+                errors.into_iter().chain(nested_errors)
             })
             .collect::<Vec<ValidationError>>();
 
@@ -40,26 +72,490 @@ pub trait Validatable {
     }
 }
 
+pub trait ValidateNested {
+    fn validate_nested(&self, config: &AppConfig) -> Vec<ValidationError>;
+}
+
+impl<T> ValidateNested for T
+where
+    T: Validatable,
+{
+    fn validate_nested(&self, config: &AppConfig) -> Vec<ValidationError> {
+        self.validate(config).err().unwrap_or_default()
+    }
+}
+
+impl<T> ValidateNested for Vec<T>
+where
+    T: ValidateNested,
+{
+    fn validate_nested(&self, config: &AppConfig) -> Vec<ValidationError> {
+        self.iter()
+            .enumerate()
+            .flat_map(|(index, value)| {
+                value
+                    .validate_nested(config)
+                    .into_iter()
+                    .map(move |error| error.nested_in(&index.to_string()))
+            })
+            .collect()
+    }
+}
+
+impl<T> ValidateNested for Option<T>
+where
+    T: ValidateNested,
+{
+    fn validate_nested(&self, config: &AppConfig) -> Vec<ValidationError> {
+        self.as_ref()
+            .map(|value| value.validate_nested(config))
+            .unwrap_or_default()
+    }
+}
+
+fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
 pub fn is_email_valid(email: &str) -> Option<Vec<String>> {
-    if !EmailAddress::is_valid(email) {
+    if !EmailAddress::is_valid(email.trim()) {
         Some(vec![format!("{email} is not a valid email address.")])
     } else {
         None
     }
 }
 
-pub fn is_password_valid(password: &str) -> Option<Vec<String>> {
-    match PASSWORD_REGEX.is_match(password) {
-        Ok(is_match) => {
-            if is_match {
-                None
-            } else {
-                Some(vec![INVALID_PASSWORD_MESSAGE.to_string()])
+pub fn is_http_url_valid(url: &str) -> Option<Vec<String>> {
+    let is_valid = url.len() <= 2048
+        && (url.starts_with("https://") || url.starts_with("http://"))
+        && url.split_once("://").is_some_and(|(_, rest)| {
+            !rest.is_empty() && !rest.starts_with('/') && !rest.chars().any(char::is_whitespace)
+        });
+
+    if is_valid {
+        None
+    } else {
+        Some(vec![format!("{url} is not a valid http(s) URL.")])
+    }
+}
+
+// Accepts BCP 47 style tags such as `en`, `en-GB` or `zh-Hant-TW`.
+pub fn is_locale_valid(locale: &str) -> Option<Vec<String>> {
+    let mut subtags = locale.split('-');
+    let is_valid = subtags.next().is_some_and(|language| {
+        (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic())
+    }) && subtags.all(|subtag| {
+        (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+
+    if is_valid {
+        None
+    } else {
+        Some(vec![format!("{locale} is not a valid locale.")])
+    }
+}
+
+pub fn is_timezone_valid(timezone: &str) -> Option<Vec<String>> {
+    if timezone.parse::<chrono_tz::Tz>().is_ok() {
+        None
+    } else {
+        Some(vec![format!("{timezone} is not a valid IANA time zone.")])
+    }
+}
+
+// Usernames can be used to sign in instead of an email, so they must not contain `@`.
+pub fn is_username_valid(username: &str) -> Option<Vec<String>> {
+    let mut errors = vec![];
+
+    let length = username.chars().count();
+    if !(3..=32).contains(&length) {
+        errors.push("Username must be between 3 and 32 characters long.".into());
+    }
+
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+    {
+        errors.push(
+            "Username may only contain letters, digits, underscores, dots and dashes.".into(),
+        );
+    }
+
+    if !username
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric())
+    {
+        errors.push("Username must start with a letter or a digit.".into());
+    }
+
+    if errors.is_empty() {
+        None
+    } else {
+        Some(errors)
+    }
+}
+
+// Rejects domains outside of the allow list, on the deny list or known to hand out disposable
+// addresses. Subdomains are matched as well, so `example.com` also covers `mail.example.com`.
+pub fn is_email_domain_allowed(email: &str, policy: &RegistrationPolicy) -> Option<Vec<String>> {
+    let (_, domain) = email.rsplit_once('@')?;
+    let domain = domain.trim().to_lowercase();
+    let matches = |candidate: &str| {
+        domain == candidate
+            || domain
+                .strip_suffix(candidate)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    };
+
+    let mut errors = vec![];
+
+    if !policy.allowed_domains.is_empty()
+        && !policy
+            .allowed_domains
+            .iter()
+            .any(|allowed| matches(allowed))
+    {
+        errors.push(format!(
+            "Registration is restricted to email addresses from {}.",
+            policy.allowed_domains.join(", ")
+        ));
+    }
+
+    if policy.denied_domains.iter().any(|denied| matches(denied)) {
+        errors.push(format!("Email addresses from {domain} are not allowed."));
+    }
+
+    // The list is large, so each suffix of the domain is looked up instead of scanning it.
+    let mut suffixes = std::iter::successors(Some(domain.as_str()), |suffix| {
+        suffix.split_once('.').map(|(_, parent)| parent)
+    });
+    if policy.block_disposable && suffixes.any(|suffix| disposable_email_domains().contains(suffix))
+    {
+        errors.push("Disposable email addresses are not allowed.".into());
+    }
+
+    if errors.is_empty() {
+        None
+    } else {
+        Some(errors)
+    }
+}
+
+fn disposable_email_domains() -> &'static HashSet<&'static str> {
+    static DOMAINS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+
+    DOMAINS.get_or_init(|| {
+        include_str!("disposable_email_domains.txt")
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect()
+    })
+}
+
+// Signing in only bounds the length, so hashing stays cheap. Passwords set before the policy was
+// tightened must keep working.
+pub fn is_login_password_valid(password: &str, policy: &PasswordPolicy) -> Option<Vec<String>> {
+    if password.chars().count() > policy.max_length {
+        Some(vec![format!(
+            "Password must be at most {} characters long.",
+            policy.max_length
+        )])
+    } else {
+        None
+    }
+}
+
+pub fn is_password_valid(
+    password: &str,
+    email: Option<&str>,
+    policy: &PasswordPolicy,
+) -> Option<Vec<String>> {
+    let length = password.chars().count();
+    let mut errors = vec![];
+
+    if length < policy.min_length {
+        errors.push(format!(
+            "Password must be at least {} characters long.",
+            policy.min_length
+        ));
+    }
+
+    if length > policy.max_length {
+        errors.push(format!(
+            "Password must be at most {} characters long.",
+            policy.max_length
+        ));
+    }
+
+    if policy.require_lowercase && !password.chars().any(char::is_lowercase) {
+        errors.push("Password must contain at least 1 lowercase letter.".into());
+    }
+
+    if policy.require_uppercase && !password.chars().any(char::is_uppercase) {
+        errors.push("Password must contain at least 1 uppercase letter.".into());
+    }
+
+    if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        errors.push("Password must contain at least 1 number.".into());
+    }
+
+    if policy.require_special && !password.chars().any(|c| !c.is_alphanumeric()) {
+        errors.push("Password must contain at least 1 special character.".into());
+    }
+
+    if policy.forbid_email
+        && email.is_some_and(|email| password.trim().eq_ignore_ascii_case(email.trim()))
+    {
+        errors.push("Password must not be the same as your email address.".into());
+    }
+
+    if password_strength(password, email) < policy.min_strength {
+        errors.push(
+            "Password is too easy to guess. Avoid common words, sequences and repeated characters."
+                .into(),
+        );
+    }
+
+    if errors.is_empty() {
+        None
+    } else {
+        Some(errors)
+    }
+}
+
+// Looks the password up in a local copy of the Have I Been Pwned range files. Each
+// `{prefix}.txt` holds the `{suffix}:{count}` lines for one 5 character SHA-1 prefix, so a check
+// only reads the one small file for the password's prefix, on the blocking pool.
+pub async fn validate_password_not_breached(
+    password: &str,
+    policy: &PasswordPolicy,
+) -> ApiResult<()> {
+    let Some(dir) = &policy.breached_passwords_dir else {
+        return Ok(());
+    };
+
+    let hash: String = Sha1::digest(password.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect();
+    let (prefix, suffix) = hash.split_at(5);
+    let path = dir.join(format!("{prefix}.txt"));
+    let suffix = suffix.to_owned();
+
+    let breached = tokio::task::spawn_blocking(move || range_contains(&path, &suffix))
+        .await
+        .map_err(io::Error::other)
+        .and_then(|breached| breached)
+        .map_err(ApiError::BreachedPasswordsError)?;
+
+    if breached {
+        return Err(ApiError::ValidationError(vec![ValidationError::new(
+            "password",
+            vec![
+                "Password has appeared in a data breach. Please choose a different password."
+                    .into(),
+            ],
+        )]));
+    }
+
+    Ok(())
+}
+
+fn range_contains(path: &Path, suffix: &str) -> io::Result<bool> {
+    let range = match fs::read_to_string(path) {
+        Ok(range) => range,
+        // A prefix without a range file has no breached passwords.
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err),
+    };
+
+    // Padding entries have a count of 0 and are not actual breached passwords.
+    Ok(range.lines().any(|line| {
+        line.split_once(':').is_some_and(|(candidate, count)| {
+            candidate.trim().eq_ignore_ascii_case(suffix) && count.trim() != "0"
+        })
+    }))
+}
+
+pub fn merge_errors<const N: usize>(errors: [Option<Vec<String>>; N]) -> Option<Vec<String>> {
+    let errors = errors
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect::<Vec<String>>();
+
+    if errors.is_empty() {
+        None
+    } else {
+        Some(errors)
+    }
+}
+
+// Scores a password from 0 (too guessable) to 4 (very unguessable) using the same guess
+// thresholds as zxcvbn, after discounting repeats, sequences and common fragments.
+fn password_strength(password: &str, email: Option<&str>) -> u8 {
+    let lowercase = password.to_lowercase();
+    let chars = lowercase.chars().collect::<Vec<char>>();
+
+    let mut charset = 0;
+    if password.chars().any(char::is_lowercase) {
+        charset += 26;
+    }
+    if password.chars().any(char::is_uppercase) {
+        charset += 26;
+    }
+    if password.chars().any(|c| c.is_ascii_digit()) {
+        charset += 10;
+    }
+    if password.chars().any(|c| !c.is_alphanumeric()) {
+        charset += 33;
+    }
+
+    let mut effective_length = chars
+        .iter()
+        .enumerate()
+        .filter(|(index, c)| {
+            let Some(previous) = index.checked_sub(1).map(|index| chars[index]) else {
+                return true;
+            };
+            let is_repeat = previous == **c;
+            let is_sequence = (**c as i64 - previous as i64).abs() == 1;
+
+            !is_repeat && !is_sequence
+        })
+        .count() as f64;
+
+    let email_local_part = email
+        .and_then(|email| email.split_once('@'))
+        .map(|(local_part, _)| local_part.to_lowercase())
+        .filter(|local_part| local_part.len() >= 3);
+    let fragments = COMMON_PASSWORD_FRAGMENTS
+        .iter()
+        .map(|fragment| fragment.to_string())
+        .chain(email_local_part);
+
+    for fragment in fragments {
+        if lowercase.contains(&fragment) {
+            effective_length -= fragment.chars().count() as f64 - 1.0;
+        }
+    }
+
+    let bits = effective_length.max(0.0) * (charset.max(1) as f64).log2();
+    let guesses = 2f64.powf(bits);
+
+    match guesses {
+        guesses if guesses < 1e3 => 0,
+        guesses if guesses < 1e6 => 1,
+        guesses if guesses < 1e8 => 2,
+        guesses if guesses < 1e10 => 3,
+        _ => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Address {
+        postcode: String,
+    }
+
+    impl Validatable for Address {
+        fn validated_properties() -> Vec<String> {
+            vec!["postcode".into()]
+        }
+
+        fn validate_property(&self, property: &str, _: &AppConfig) -> Option<Vec<String>> {
+            match property {
+                "postcode" if self.postcode.is_empty() => {
+                    Some(vec!["Postcode must not be empty.".into()])
+                }
+                _ => None,
             }
         }
-        Err(err) => {
-            tracing::error!("{err}");
-            panic!("{err}");
+    }
+
+    struct Customer {
+        address: Address,
+        addresses: Vec<Address>,
+        billing_address: Option<Address>,
+        shipping_address: Option<Address>,
+    }
+
+    impl Validatable for Customer {
+        fn validated_properties() -> Vec<String> {
+            vec![
+                "address".into(),
+                "addresses".into(),
+                "billingAddress".into(),
+                "shipping/address".into(),
+            ]
+        }
+
+        fn validate_property(&self, _: &str, _: &AppConfig) -> Option<Vec<String>> {
+            None
         }
+
+        fn validate_nested_property(
+            &self,
+            property: &str,
+            config: &AppConfig,
+        ) -> Vec<ValidationError> {
+            match property {
+                "address" => self.address.validate_nested(config),
+                "addresses" => self.addresses.validate_nested(config),
+                "billingAddress" => self.billing_address.validate_nested(config),
+                "shipping/address" => self.shipping_address.validate_nested(config),
+                _ => vec![],
+            }
+        }
+    }
+
+    fn address(postcode: &str) -> Address {
+        Address {
+            postcode: postcode.into(),
+        }
+    }
+
+    #[test]
+    fn validate_reports_nested_errors_as_json_pointers() {
+        let customer = Customer {
+            address: address(""),
+            addresses: vec![address("1011"), address(""), address("")],
+            billing_address: Some(address("")),
+            shipping_address: Some(address("")),
+        };
+
+        let properties = customer
+            .validate(&AppConfig::new())
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.property)
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            properties,
+            [
+                "address/postcode",
+                "addresses/1/postcode",
+                "addresses/2/postcode",
+                "billingAddress/postcode",
+                "shipping~1address/postcode",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_skips_missing_and_valid_nested_values() {
+        let customer = Customer {
+            address: address("1011"),
+            addresses: vec![address("1011")],
+            billing_address: None,
+            shipping_address: None,
+        };
+
+        assert!(customer.validate(&AppConfig::new()).is_ok());
     }
 }
@@ -0,0 +1,246 @@
+use sqlx::{Sqlite, SqliteConnection};
+
+use crate::{
+    core::{
+        error::{ApiError, ApiResult},
+        models::{Page, PageRequest},
+        types::DbDateTime,
+    },
+    invitation::{
+        entities::invitation::{CreateInvitationEntity, InvitationEntity, InvitationFilter},
+        error::InvitationError,
+    },
+    organization::entities::organization::OrganizationRole,
+};
+
+const INVITATION_FILTER: &str = "
+    WHERE (?1 IS NULL OR i.organization_id = ?1)
+    ";
+
+pub async fn find_invitation_by_id(
+    connection: &mut SqliteConnection,
+    id: i64,
+) -> ApiResult<Option<InvitationEntity>> {
+    sqlx::query_as!(
+        InvitationEntity,
+        r#"
+        SELECT
+            i.id,
+            i.created_at as "created_at: DbDateTime",
+            i.email,
+            i.role_id,
+            r.name as "role?",
+            i.organization_id,
+            i.organization_role as "organization_role: OrganizationRole",
+            i.invited_by,
+            i.expires_at as "expires_at: DbDateTime",
+            i.accepted_at as "accepted_at: DbDateTime",
+            i.accepted_by,
+            i.revoked_at as "revoked_at: DbDateTime"
+        FROM invitations i
+        LEFT JOIN roles r ON r.id = i.role_id
+        WHERE i.id = ?
+        "#,
+        id
+    )
+    .fetch_optional(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn find_invitation_by_token(
+    connection: &mut SqliteConnection,
+    token: &str,
+) -> ApiResult<Option<InvitationEntity>> {
+    sqlx::query_as!(
+        InvitationEntity,
+        r#"
+        SELECT
+            i.id,
+            i.created_at as "created_at: DbDateTime",
+            i.email,
+            i.role_id,
+            r.name as "role?",
+            i.organization_id,
+            i.organization_role as "organization_role: OrganizationRole",
+            i.invited_by,
+            i.expires_at as "expires_at: DbDateTime",
+            i.accepted_at as "accepted_at: DbDateTime",
+            i.accepted_by,
+            i.revoked_at as "revoked_at: DbDateTime"
+        FROM invitations i
+        LEFT JOIN roles r ON r.id = i.role_id
+        WHERE i.token = ?
+        "#,
+        token
+    )
+    .fetch_optional(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn find_paginated_invitations(
+    connection: &mut SqliteConnection,
+    filter: InvitationFilter,
+    request: PageRequest,
+) -> ApiResult<Page<InvitationEntity>> {
+    let sql = request.to_sql_string(&format!(
+        "
+        SELECT
+            i.id,
+            i.created_at,
+            i.email,
+            i.role_id,
+            r.name as role,
+            i.organization_id,
+            i.organization_role,
+            i.invited_by,
+            i.expires_at,
+            i.accepted_at,
+            i.accepted_by,
+            i.revoked_at
+        FROM invitations i
+        LEFT JOIN roles r ON r.id = i.role_id
+        {INVITATION_FILTER}
+        "
+    ));
+
+    let invitations = sqlx::query_as::<Sqlite, InvitationEntity>(&sql)
+        .bind(filter.organization_id)
+        .fetch_all(&mut *connection)
+        .await
+        .map_err(ApiError::from)?;
+
+    let count = sqlx::query_scalar::<Sqlite, i64>(&format!(
+        "SELECT COUNT(1) FROM invitations i {INVITATION_FILTER}"
+    ))
+    .bind(filter.organization_id)
+    .fetch_one(connection)
+    .await
+    .map(|count| count as u64)
+    .map_err(ApiError::from)?;
+
+    Ok(Page::new(invitations, count, request))
+}
+
+pub async fn pending_invitation_exists(
+    connection: &mut SqliteConnection,
+    email: &str,
+    organization_id: Option<i64>,
+) -> ApiResult<bool> {
+    let now = DbDateTime::now();
+    sqlx::query!(
+        "
+        SELECT COUNT(1) as count
+        FROM invitations
+        WHERE email = ? COLLATE NOCASE
+          AND organization_id IS ?
+          AND accepted_at IS NULL
+          AND revoked_at IS NULL
+          AND expires_at > ?
+        ",
+        email,
+        organization_id,
+        now
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count > 0)
+    .map_err(ApiError::from)
+}
+
+pub async fn create_invitation(
+    connection: &mut SqliteConnection,
+    invitation: CreateInvitationEntity,
+) -> ApiResult<InvitationEntity> {
+    let id = sqlx::query!(
+        "
+        INSERT INTO invitations (
+            email,
+            token,
+            role_id,
+            organization_id,
+            organization_role,
+            invited_by,
+            expires_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        RETURNING id
+        ",
+        invitation.email,
+        invitation.token,
+        invitation.role_id,
+        invitation.organization_id,
+        invitation.organization_role,
+        invitation.invited_by,
+        invitation.expires_at
+    )
+    .fetch_one(&mut *connection)
+    .await
+    .map(|result| result.id)
+    .map_err(ApiError::from)?;
+
+    find_invitation_by_id(connection, id)
+        .await?
+        .ok_or(ApiError::InvitationError(
+            InvitationError::InvitationDoesNotExist(id),
+        ))
+}
+
+pub async fn revoke_invitation(connection: &mut SqliteConnection, id: i64) -> ApiResult<()> {
+    let now = DbDateTime::now();
+    sqlx::query!(
+        "UPDATE invitations SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL",
+        now,
+        id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+// Returns false when the invitation was already accepted, e.g. by a concurrent request.
+pub async fn mark_invitation_accepted(
+    connection: &mut SqliteConnection,
+    id: i64,
+    user_id: i64,
+) -> ApiResult<bool> {
+    let now = DbDateTime::now();
+    sqlx::query!(
+        "
+        UPDATE invitations
+        SET accepted_at = ?, accepted_by = ?
+        WHERE id = ?
+          AND accepted_at IS NULL
+          AND revoked_at IS NULL
+        ",
+        now,
+        user_id,
+        id
+    )
+    .execute(connection)
+    .await
+    .map(|result| result.rows_affected() > 0)
+    .map_err(ApiError::from)
+}
+
+pub async fn find_all_emails(connection: &mut SqliteConnection) -> ApiResult<Vec<(i64, String)>> {
+    sqlx::query!("SELECT id, email FROM invitations")
+        .fetch_all(connection)
+        .await
+        .map(|rows| rows.into_iter().map(|row| (row.id, row.email)).collect())
+        .map_err(ApiError::from)
+}
+
+pub async fn update_invitation_email(
+    connection: &mut SqliteConnection,
+    id: i64,
+    email: &str,
+) -> ApiResult<()> {
+    sqlx::query!("UPDATE invitations SET email = ? WHERE id = ?", email, id)
+        .execute(connection)
+        .await
+        .map(|_| ())
+        .map_err(ApiError::from)
+}
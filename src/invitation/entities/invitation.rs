@@ -0,0 +1,59 @@
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sqlx::FromRow;
+use strum::EnumIs;
+
+use crate::{core::types::DbDateTime, organization::entities::organization::OrganizationRole};
+
+#[derive(Clone, Copy, PartialEq, Serialize, JsonSchema, EnumIs)]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Revoked,
+    Expired,
+}
+
+#[derive(FromRow, Clone)]
+pub struct InvitationEntity {
+    pub id: i64,
+    pub created_at: DbDateTime,
+    pub email: String,
+    pub role_id: Option<i64>,
+    pub role: Option<String>,
+    pub organization_id: Option<i64>,
+    pub organization_role: Option<OrganizationRole>,
+    pub invited_by: Option<i64>,
+    pub expires_at: DbDateTime,
+    pub accepted_at: Option<DbDateTime>,
+    pub accepted_by: Option<i64>,
+    pub revoked_at: Option<DbDateTime>,
+}
+
+impl InvitationEntity {
+    pub fn status(&self) -> InvitationStatus {
+        if self.accepted_at.is_some() {
+            InvitationStatus::Accepted
+        } else if self.revoked_at.is_some() {
+            InvitationStatus::Revoked
+        } else if self.expires_at.0 <= Utc::now() {
+            InvitationStatus::Expired
+        } else {
+            InvitationStatus::Pending
+        }
+    }
+}
+
+pub struct CreateInvitationEntity {
+    pub email: String,
+    pub token: String,
+    pub role_id: Option<i64>,
+    pub organization_id: Option<i64>,
+    pub organization_role: Option<OrganizationRole>,
+    pub invited_by: i64,
+    pub expires_at: DbDateTime,
+}
+
+pub struct InvitationFilter {
+    pub organization_id: Option<i64>,
+}
@@ -0,0 +1,74 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::{ApiErrorResponse, ErrorCode, IntoApiErrorResponse};
+
+#[derive(Error, Debug)]
+pub enum InvitationError {
+    #[error("Invalid invitation token.")]
+    InvalidInvitationToken,
+
+    #[error("This invitation has expired.")]
+    InvitationExpired,
+
+    #[error("This invitation has been revoked.")]
+    InvitationRevoked,
+
+    #[error("This invitation has already been accepted.")]
+    InvitationAlreadyAccepted,
+
+    #[error("Invitation {0} does not exist.")]
+    InvitationDoesNotExist(i64),
+
+    #[error("A pending invitation for {0} already exists.")]
+    PendingInvitationExists(String),
+
+    #[error("A password is required to create a new account.")]
+    PasswordRequired,
+
+    #[error("Sign in to the invited account to accept this invitation.")]
+    SignInRequired,
+
+    #[error("This invitation is for another account.")]
+    InvitationForAnotherAccount,
+}
+
+impl ErrorCode for InvitationError {
+    fn code(&self) -> &str {
+        match self {
+            InvitationError::InvalidInvitationToken => "INV0001",
+            InvitationError::InvitationExpired => "INV0002",
+            InvitationError::InvitationRevoked => "INV0003",
+            InvitationError::InvitationAlreadyAccepted => "INV0004",
+            InvitationError::InvitationDoesNotExist(_) => "INV0005",
+            InvitationError::PendingInvitationExists(_) => "INV0006",
+            InvitationError::PasswordRequired => "INV0007",
+            InvitationError::SignInRequired => "INV0008",
+            InvitationError::InvitationForAnotherAccount => "INV0009",
+        }
+    }
+}
+
+impl IntoApiErrorResponse for InvitationError {
+    fn into_app_error_response(&self) -> ApiErrorResponse {
+        let status_code = match self {
+            InvitationError::InvitationDoesNotExist(_) => StatusCode::NOT_FOUND,
+            InvitationError::InvitationExpired
+            | InvitationError::InvitationRevoked
+            | InvitationError::InvitationAlreadyAccepted => StatusCode::GONE,
+            InvitationError::InvalidInvitationToken
+            | InvitationError::PendingInvitationExists(_)
+            | InvitationError::PasswordRequired => StatusCode::BAD_REQUEST,
+            InvitationError::SignInRequired => StatusCode::UNAUTHORIZED,
+            InvitationError::InvitationForAnotherAccount => StatusCode::FORBIDDEN,
+        };
+
+        ApiErrorResponse {
+            status_code,
+            code: self.code().into(),
+            message: self.to_string(),
+            debug_description: None,
+            validation_errors: vec![],
+        }
+    }
+}
@@ -0,0 +1,411 @@
+use axum::extract::State;
+use serde_json::json;
+
+use crate::{
+    account::{
+        database::{role_repository, user_repository},
+        entities::user::CreateUserEntity,
+        error::AccountError,
+        models::response::user::UserResponse,
+        utils::{extractors::CurrentUser, hash_password, hash_token},
+    },
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        extractors::{ClientIp, JsonRequest, JsonResponse},
+        AppState,
+    },
+    invitation::{
+        database::invitation_repository, entities::invitation::InvitationStatus,
+        error::InvitationError, models::request::invitation::AcceptInvitationRequest,
+        utils::verify_invitation_token,
+    },
+    organization::database::organization_repository,
+};
+
+// Creates the account for the invited email, or links the invitation to the existing account
+// with that email. Holding the token is enough to create a new account, but an existing account
+// is only changed from its own session, so a leaked token cannot alter it.
+#[axum::debug_handler]
+pub async fn accept_invitation(
+    State(state): State<AppState>,
+    current_user: Option<CurrentUser>,
+    ClientIp(ip_address): ClientIp,
+    JsonRequest(request): JsonRequest<AcceptInvitationRequest>,
+) -> ApiResult<JsonResponse<UserResponse>> {
+    if !verify_invitation_token(state.config.invitation.secret(), &request.token) {
+        return Err(ApiError::InvitationError(
+            InvitationError::InvalidInvitationToken,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+    let Some(invitation) = invitation_repository::find_invitation_by_token(
+        &mut connection,
+        &hash_token(&request.token),
+    )
+    .await?
+    else {
+        return Err(ApiError::InvitationError(
+            InvitationError::InvalidInvitationToken,
+        ));
+    };
+
+    match invitation.status() {
+        InvitationStatus::Pending => {}
+        InvitationStatus::Accepted => {
+            return Err(ApiError::InvitationError(
+                InvitationError::InvitationAlreadyAccepted,
+            ))
+        }
+        InvitationStatus::Revoked => {
+            return Err(ApiError::InvitationError(
+                InvitationError::InvitationRevoked,
+            ))
+        }
+        InvitationStatus::Expired => {
+            return Err(ApiError::InvitationError(
+                InvitationError::InvitationExpired,
+            ))
+        }
+    }
+
+    let (user, created) =
+        match user_repository::find_user_by_email(&mut connection, &invitation.email).await? {
+            Some(user) => {
+                match current_user {
+                    None => return Err(ApiError::InvitationError(InvitationError::SignInRequired)),
+                    Some(CurrentUser(_, Some(_))) => {
+                        return Err(ApiError::AccountError(
+                            AccountError::ForbiddenWhileImpersonating,
+                        ))
+                    }
+                    Some(CurrentUser(current, None)) if current.id != user.id => {
+                        return Err(ApiError::InvitationError(
+                            InvitationError::InvitationForAnotherAccount,
+                        ))
+                    }
+                    Some(_) => {}
+                }
+
+                (user, false)
+            }
+            None => {
+                let Some(password) = request.password else {
+                    return Err(ApiError::InvitationError(InvitationError::PasswordRequired));
+                };
+
+                validators::validate_new_password(&state.config, &invitation.email, &password)
+                    .await?;
+
+                let user = user_repository::create_user(
+                    &mut connection,
+                    CreateUserEntity {
+                        email: invitation.email.clone(),
+                        username: None,
+                        password: hash_password(&state.config.argon2, &password)?,
+                    },
+                )
+                .await?;
+                (user, true)
+            }
+        };
+
+    if let Some(role_id) = invitation
+        .role_id
+        .filter(|role_id| *role_id != user.role_id)
+    {
+        user_repository::update_role_by_user_id(&mut connection, user.id, role_id).await?;
+
+        let role = role_repository::find_role_by_id(&mut connection, role_id).await?;
+        let mut event = CreateAuditEventEntity::new(
+            AuditEventType::RoleChanged,
+            Some(user.id),
+            ip_address.clone(),
+        )
+        .with_details(json!({
+            "from": user.role,
+            "to": role.map(|role| role.name),
+            "invitationId": invitation.id,
+        }));
+        if let Some(invited_by) = invitation.invited_by {
+            event = event.with_actor(invited_by);
+        }
+        audit_repository::create_audit_event(&mut connection, event).await?;
+    }
+
+    if let (Some(organization_id), Some(role)) =
+        (invitation.organization_id, invitation.organization_role)
+    {
+        if organization_repository::find_member_role(&mut connection, organization_id, user.id)
+            .await?
+            .is_none()
+        {
+            organization_repository::add_member(&mut connection, organization_id, user.id, role)
+                .await?;
+
+            let mut event = CreateAuditEventEntity::new(
+                AuditEventType::OrganizationMemberAdded,
+                Some(user.id),
+                ip_address.clone(),
+            )
+            .with_details(json!({
+                "organizationId": organization_id,
+                "role": role,
+                "invitationId": invitation.id,
+            }));
+            if let Some(invited_by) = invitation.invited_by {
+                event = event.with_actor(invited_by);
+            }
+            audit_repository::create_audit_event(&mut connection, event).await?;
+        }
+    }
+
+    if !invitation_repository::mark_invitation_accepted(&mut connection, invitation.id, user.id)
+        .await?
+    {
+        return Err(ApiError::InvitationError(
+            InvitationError::InvitationAlreadyAccepted,
+        ));
+    }
+
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(
+            AuditEventType::InvitationAccepted,
+            Some(user.id),
+            ip_address,
+        )
+        .with_details(json!({ "invitationId": invitation.id, "accountCreated": created })),
+    )
+    .await?;
+
+    let user = user_repository::find_user_by_id(&mut connection, user.id)
+        .await?
+        .unwrap_or(user);
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(JsonResponse(user.into()))
+}
+
+pub mod admin {
+    use axum::{
+        extract::{Path, State},
+        response::NoContent,
+    };
+    use axum_extra::extract::Query;
+    use serde_json::json;
+
+    use crate::{
+        account::{
+            database::role_repository,
+            error::AccountError,
+            utils::extractors::{
+                CurrentUser, ManageInvitations, ManageRoles, Permission, RequirePermission,
+            },
+        },
+        audit::{
+            database::audit_repository,
+            entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+        },
+        core::{
+            error::{ApiError, ApiResult},
+            extractors::{ClientIp, JsonResponse, ValidJsonRequest},
+            models::{Page, PageRequest},
+            AppState,
+        },
+        invitation::{
+            database::invitation_repository,
+            error::InvitationError,
+            models::{
+                request::invitation::{CreateInvitationRequest, InvitationFilterRequest},
+                response::invitation::InvitationResponse,
+            },
+            utils::issue_invitation,
+        },
+        organization::{
+            database::organization_repository, entities::organization::OrganizationRole,
+            error::OrganizationError,
+        },
+    };
+
+    #[axum::debug_handler]
+    pub async fn get_paginated_invitations(
+        State(state): State<AppState>,
+        RequirePermission(_): RequirePermission<ManageInvitations>,
+        Query(filter): Query<InvitationFilterRequest>,
+        Query(request): Query<PageRequest>,
+    ) -> ApiResult<JsonResponse<Page<InvitationResponse>>> {
+        let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+        invitation_repository::find_paginated_invitations(&mut connection, filter.into(), request)
+            .await
+            .map(|invitations| invitations.map(|invitation| invitation.to_owned().into()))
+            .map(JsonResponse)
+    }
+
+    #[axum::debug_handler]
+    pub async fn create_invitation(
+        State(state): State<AppState>,
+        CurrentUser(admin, impersonation): CurrentUser,
+        RequirePermission(_): RequirePermission<ManageInvitations>,
+        ClientIp(ip_address): ClientIp,
+        ValidJsonRequest(request): ValidJsonRequest<CreateInvitationRequest>,
+    ) -> ApiResult<JsonResponse<InvitationResponse>> {
+        if impersonation.is_some() {
+            return Err(ApiError::AccountError(
+                AccountError::ForbiddenWhileImpersonating,
+            ));
+        }
+
+        let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+        // Inviting with a role grants it, so it takes the same rights as assigning it directly.
+        if let Some(role_id) = request.role_id {
+            if role_repository::find_role_by_id(&mut connection, role_id)
+                .await?
+                .is_none()
+            {
+                return Err(ApiError::AccountError(AccountError::RoleDoesNotExist(
+                    role_id,
+                )));
+            }
+
+            let admin_permissions =
+                role_repository::find_permissions_by_role_id(&mut connection, admin.role_id)
+                    .await?;
+            if !admin_permissions.contains(&ManageRoles::permission().to_string()) {
+                return Err(ApiError::AccountError(AccountError::MissingPermission(
+                    ManageRoles::permission().into(),
+                )));
+            }
+
+            if role_repository::find_permissions_by_role_id(&mut connection, role_id)
+                .await?
+                .iter()
+                .any(|permission| !admin_permissions.contains(permission))
+            {
+                return Err(ApiError::AccountError(AccountError::InsufficientPrivilege));
+            }
+        }
+
+        if let Some(organization_id) = request.organization_id {
+            if organization_repository::find_organization_by_id(&mut connection, organization_id)
+                .await?
+                .is_none()
+            {
+                return Err(ApiError::OrganizationError(
+                    OrganizationError::OrganizationDoesNotExist(organization_id),
+                ));
+            }
+        }
+
+        let organization = request.organization_id.map(|organization_id| {
+            (
+                organization_id,
+                request
+                    .organization_role
+                    .unwrap_or(OrganizationRole::Member),
+            )
+        });
+        let invitation = issue_invitation(
+            &mut connection,
+            &state.config,
+            admin.id,
+            request.email,
+            request.role_id,
+            organization,
+            ip_address,
+        )
+        .await?;
+
+        connection.commit().await.map_err(ApiError::from)?;
+
+        Ok(JsonResponse(invitation.into()))
+    }
+
+    #[axum::debug_handler]
+    pub async fn revoke_invitation(
+        State(state): State<AppState>,
+        CurrentUser(admin, impersonation): CurrentUser,
+        RequirePermission(_): RequirePermission<ManageInvitations>,
+        ClientIp(ip_address): ClientIp,
+        Path(id): Path<i64>,
+    ) -> ApiResult<NoContent> {
+        if impersonation.is_some() {
+            return Err(ApiError::AccountError(
+                AccountError::ForbiddenWhileImpersonating,
+            ));
+        }
+
+        let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+        let Some(invitation) =
+            invitation_repository::find_invitation_by_id(&mut connection, id).await?
+        else {
+            return Err(ApiError::InvitationError(
+                InvitationError::InvitationDoesNotExist(id),
+            ));
+        };
+
+        super::validators::validate_revocable(&invitation)?;
+        invitation_repository::revoke_invitation(&mut connection, invitation.id).await?;
+
+        audit_repository::create_audit_event(
+            &mut connection,
+            CreateAuditEventEntity::new(AuditEventType::InvitationRevoked, None, ip_address)
+                .with_actor(admin.id)
+                .with_details(json!({ "invitationId": invitation.id })),
+        )
+        .await?;
+
+        connection.commit().await.map_err(ApiError::from)?;
+
+        Ok(NoContent)
+    }
+}
+
+pub(super) mod validators {
+    use crate::{
+        core::{
+            error::{ApiError, ApiResult},
+            validators::{self, ValidationError},
+            AppConfig,
+        },
+        invitation::{
+            entities::invitation::{InvitationEntity, InvitationStatus},
+            error::InvitationError,
+        },
+    };
+
+    pub async fn validate_new_password(
+        config: &AppConfig,
+        email: &str,
+        password: &str,
+    ) -> ApiResult<()> {
+        let policy = &config.password_policy;
+
+        if let Some(errors) = validators::is_password_valid(password, Some(email), policy) {
+            return Err(ApiError::ValidationError(vec![ValidationError::new(
+                "password", errors,
+            )]));
+        }
+
+        validators::validate_password_not_breached(password, policy).await
+    }
+
+    pub fn validate_revocable(invitation: &InvitationEntity) -> ApiResult<()> {
+        match invitation.status() {
+            InvitationStatus::Accepted => Err(ApiError::InvitationError(
+                InvitationError::InvitationAlreadyAccepted,
+            )),
+            InvitationStatus::Revoked => Err(ApiError::InvitationError(
+                InvitationError::InvitationRevoked,
+            )),
+            InvitationStatus::Pending | InvitationStatus::Expired => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use axum::{
+    extract::{Path, State},
+    response::NoContent,
+};
+use axum_extra::extract::Query;
+use serde_json::json;
+
+use crate::{
+    account::{error::AccountError, utils::extractors::CurrentUser},
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        extractors::{ClientIp, JsonResponse, ValidJsonRequest},
+        models::{Page, PageRequest},
+        AppState,
+    },
+    invitation::{
+        database::invitation_repository,
+        entities::invitation::InvitationFilter,
+        error::InvitationError,
+        models::{
+            request::invitation::CreateOrganizationInvitationRequest,
+            response::invitation::InvitationResponse,
+        },
+        utils::issue_invitation,
+    },
+    organization::{error::OrganizationError, utils::extractors::CurrentOrg},
+};
+
+#[axum::debug_handler]
+pub async fn get_organization_invitations(
+    State(state): State<AppState>,
+    CurrentOrg(organization, role): CurrentOrg,
+    Query(request): Query<PageRequest>,
+) -> ApiResult<JsonResponse<Page<InvitationResponse>>> {
+    if !role.can_manage_members() {
+        return Err(ApiError::OrganizationError(
+            OrganizationError::InsufficientOrganizationRole,
+        ));
+    }
+
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+    invitation_repository::find_paginated_invitations(
+        &mut connection,
+        InvitationFilter {
+            organization_id: Some(organization.id),
+        },
+        request,
+    )
+    .await
+    .map(|invitations| invitations.map(|invitation| invitation.to_owned().into()))
+    .map(JsonResponse)
+}
+
+#[axum::debug_handler]
+pub async fn create_organization_invitation(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+    CurrentOrg(organization, role): CurrentOrg,
+    ClientIp(ip_address): ClientIp,
+    ValidJsonRequest(request): ValidJsonRequest<CreateOrganizationInvitationRequest>,
+) -> ApiResult<JsonResponse<InvitationResponse>> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    if !role.can_grant(request.role) {
+        return Err(ApiError::OrganizationError(
+            OrganizationError::InsufficientOrganizationRole,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+    let invitation = issue_invitation(
+        &mut connection,
+        &state.config,
+        user.id,
+        request.email,
+        None,
+        Some((organization.id, request.role)),
+        ip_address,
+    )
+    .await?;
+
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(JsonResponse(invitation.into()))
+}
+
+#[axum::debug_handler]
+pub async fn revoke_organization_invitation(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+    CurrentOrg(organization, role): CurrentOrg,
+    ClientIp(ip_address): ClientIp,
+    Path(id): Path<i64>,
+) -> ApiResult<NoContent> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+    // Invitations of other organizations are reported as missing rather than forbidden.
+    let Some(invitation) = invitation_repository::find_invitation_by_id(&mut connection, id)
+        .await?
+        .filter(|invitation| invitation.organization_id == Some(organization.id))
+    else {
+        return Err(ApiError::InvitationError(
+            InvitationError::InvitationDoesNotExist(id),
+        ));
+    };
+
+    if !invitation
+        .organization_role
+        .is_some_and(|invited_role| role.can_grant(invited_role))
+    {
+        return Err(ApiError::OrganizationError(
+            OrganizationError::InsufficientOrganizationRole,
+        ));
+    }
+
+    super::invitation::validators::validate_revocable(&invitation)?;
+    invitation_repository::revoke_invitation(&mut connection, invitation.id).await?;
+
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(AuditEventType::InvitationRevoked, None, ip_address)
+            .with_actor(user.id)
+            .with_details(json!({
+                "invitationId": invitation.id,
+                "organizationId": organization.id,
+            })),
+    )
+    .await?;
+
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(NoContent)
+}
@@ -0,0 +1,9 @@
+pub mod database;
+pub mod entities;
+pub mod error;
+pub mod handlers;
+pub mod models;
+mod router;
+pub mod utils;
+
+pub use router::*;
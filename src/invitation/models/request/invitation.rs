@@ -0,0 +1,76 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::{
+    core::{
+        validators::{self, Validatable},
+        AppConfig,
+    },
+    invitation::entities::invitation::InvitationFilter,
+    organization::entities::organization::OrganizationRole,
+};
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInvitationRequest {
+    pub email: String,
+    pub role_id: Option<i64>,
+    pub organization_id: Option<i64>,
+    /// Defaults to `Member` when an organization is given.
+    pub organization_role: Option<OrganizationRole>,
+}
+
+impl Validatable for CreateInvitationRequest {
+    fn validated_properties() -> Vec<String> {
+        vec!["email".into()]
+    }
+
+    fn validate_property(&self, property: &str, _: &AppConfig) -> Option<Vec<String>> {
+        match property {
+            "email" => validators::is_email_valid(&self.email),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrganizationInvitationRequest {
+    pub email: String,
+    pub role: OrganizationRole,
+}
+
+impl Validatable for CreateOrganizationInvitationRequest {
+    fn validated_properties() -> Vec<String> {
+        vec!["email".into()]
+    }
+
+    fn validate_property(&self, property: &str, _: &AppConfig) -> Option<Vec<String>> {
+        match property {
+            "email" => validators::is_email_valid(&self.email),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptInvitationRequest {
+    pub token: String,
+    /// Required when no account exists yet for the invited email.
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvitationFilterRequest {
+    pub organization_id: Option<i64>,
+}
+
+impl From<InvitationFilterRequest> for InvitationFilter {
+    fn from(value: InvitationFilterRequest) -> Self {
+        Self {
+            organization_id: value.organization_id,
+        }
+    }
+}
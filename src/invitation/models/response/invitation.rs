@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::{
+    invitation::entities::invitation::{InvitationEntity, InvitationStatus},
+    organization::entities::organization::OrganizationRole,
+};
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvitationResponse {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub email: String,
+    pub role: Option<String>,
+    pub organization_id: Option<i64>,
+    pub organization_role: Option<OrganizationRole>,
+    pub invited_by: Option<i64>,
+    pub expires_at: DateTime<Utc>,
+    pub status: InvitationStatus,
+}
+
+impl From<InvitationEntity> for InvitationResponse {
+    fn from(value: InvitationEntity) -> Self {
+        Self {
+            id: value.id,
+            status: value.status(),
+            created_at: value.created_at.into(),
+            email: value.email,
+            role: value.role,
+            organization_id: value.organization_id,
+            organization_role: value.organization_role,
+            invited_by: value.invited_by,
+            expires_at: value.expires_at.into(),
+        }
+    }
+}
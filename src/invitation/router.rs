@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use aide::axum::{
+    routing::{delete, get, post},
+    ApiRouter,
+};
+use axum::middleware::from_fn_with_state;
+
+use crate::core::{
+    constants::openapi::{
+        tags::{admin, INVITATION_TAG},
+        DEFAULT_SECURITY_SCHEME,
+    },
+    rate_limit::{rate_limit, RateLimitKey, RateLimitPolicy, RateLimiter},
+    AppState,
+};
+
+use super::handlers;
+
+const ACCEPT_INVITATION_BY_IP: RateLimitPolicy = RateLimitPolicy::new(
+    "accept-invitation:ip",
+    RateLimitKey::Ip,
+    10,
+    Duration::from_secs(60),
+);
+
+pub fn router(state: AppState) -> ApiRouter {
+    ApiRouter::new().nest(
+        "/invitation",
+        ApiRouter::new()
+            .api_route_with(
+                "/accept",
+                post(handlers::invitation::accept_invitation).route_layer(from_fn_with_state(
+                    RateLimiter::new(&state, &[ACCEPT_INVITATION_BY_IP]),
+                    rate_limit,
+                )),
+                |op| op.tag(INVITATION_TAG),
+            )
+            .api_route_with(
+                "/organization",
+                get(handlers::organization::get_organization_invitations)
+                    .post(handlers::organization::create_organization_invitation),
+                |op| {
+                    op.tag(INVITATION_TAG)
+                        .security_requirement(DEFAULT_SECURITY_SCHEME)
+                },
+            )
+            .api_route_with(
+                "/organization/{id}",
+                delete(handlers::organization::revoke_organization_invitation),
+                |op| {
+                    op.tag(INVITATION_TAG)
+                        .security_requirement(DEFAULT_SECURITY_SCHEME)
+                },
+            )
+            .api_route_with(
+                "/admin",
+                get(handlers::invitation::admin::get_paginated_invitations)
+                    .post(handlers::invitation::admin::create_invitation),
+                |op| {
+                    op.tag(admin::INVITATION_TAG)
+                        .security_requirement(DEFAULT_SECURITY_SCHEME)
+                },
+            )
+            .api_route_with(
+                "/admin/{id}",
+                delete(handlers::invitation::admin::revoke_invitation),
+                |op| {
+                    op.tag(admin::INVITATION_TAG)
+                        .security_requirement(DEFAULT_SECURITY_SCHEME)
+                },
+            )
+            .with_state(state.clone()),
+    )
+}
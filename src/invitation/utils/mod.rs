@@ -0,0 +1,155 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::SqliteConnection;
+use uuid::Uuid;
+
+use crate::{
+    account::{
+        database::user_repository,
+        utils::{hash_token, normalize_email},
+    },
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        types::DbDateTime,
+        AppConfig,
+    },
+    invitation::{
+        database::invitation_repository,
+        entities::invitation::{CreateInvitationEntity, InvitationEntity},
+        error::InvitationError,
+    },
+    notification::{
+        dispatcher,
+        entities::notification::{CreateNotificationEntity, NotificationType},
+    },
+    organization::{
+        database::organization_repository, entities::organization::OrganizationRole,
+        error::OrganizationError,
+    },
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(secret: &str, nonce: &str) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(nonce.as_bytes());
+    mac
+}
+
+// Tokens have the form `{nonce}.{signature}`, so forged tokens are rejected before hitting the
+// database. Only the hash of the token is stored.
+pub fn generate_invitation_token(secret: &str) -> String {
+    let nonce = Uuid::new_v4().simple().to_string();
+    let signature: String = mac(secret, &nonce)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+
+    format!("{nonce}.{signature}")
+}
+
+pub fn verify_invitation_token(secret: &str, token: &str) -> bool {
+    let Some((nonce, signature)) = token.split_once('.') else {
+        return false;
+    };
+
+    let signature = (0..signature.len())
+        .step_by(2)
+        .map(|i| {
+            signature
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>();
+
+    signature.is_some_and(|signature| mac(secret, nonce).verify_slice(&signature).is_ok())
+}
+
+pub async fn issue_invitation(
+    connection: &mut SqliteConnection,
+    config: &AppConfig,
+    invited_by: i64,
+    email: String,
+    role_id: Option<i64>,
+    organization: Option<(i64, OrganizationRole)>,
+    ip_address: Option<String>,
+) -> ApiResult<InvitationEntity> {
+    let email = normalize_email(config, &email);
+    let organization_id = organization.map(|(id, _)| id);
+
+    if invitation_repository::pending_invitation_exists(connection, &email, organization_id).await?
+    {
+        return Err(ApiError::InvitationError(
+            InvitationError::PendingInvitationExists(email),
+        ));
+    }
+
+    let mut organization_name = None;
+    if let Some(organization_id) = organization_id {
+        organization_name =
+            organization_repository::find_organization_by_id(connection, organization_id)
+                .await?
+                .map(|organization| organization.name);
+
+        if let Some(user) = user_repository::find_user_by_email(connection, &email).await? {
+            if organization_repository::find_member_role(connection, organization_id, user.id)
+                .await?
+                .is_some()
+            {
+                return Err(ApiError::OrganizationError(
+                    OrganizationError::AlreadyAMember(user.email),
+                ));
+            }
+        }
+    }
+
+    let token = generate_invitation_token(config.invitation.secret());
+    let invitation = invitation_repository::create_invitation(
+        connection,
+        CreateInvitationEntity {
+            email,
+            token: hash_token(&token),
+            role_id,
+            organization_id,
+            organization_role: organization.map(|(_, role)| role),
+            invited_by,
+            expires_at: DbDateTime(Utc::now() + config.invitation.validity_duration),
+        },
+    )
+    .await?;
+
+    audit_repository::create_audit_event(
+        connection,
+        CreateAuditEventEntity::new(AuditEventType::InvitationCreated, None, ip_address)
+            .with_actor(invited_by)
+            .with_details(json!({
+                "invitationId": invitation.id,
+                "organizationId": invitation.organization_id,
+            })),
+    )
+    .await?;
+
+    // The token goes straight to the invitee, so the inviter never holds it.
+    dispatcher::notify(
+        connection,
+        config,
+        CreateNotificationEntity::to_email(NotificationType::Invitation, invitation.email.clone())
+            .with_details(json!({
+                "token": token,
+                "expiresAt": invitation.expires_at.0.to_rfc2822(),
+                "organization": organization_name,
+            })),
+    )
+    .await?;
+
+    Ok(invitation)
+}
@@ -1,2 +1,6 @@
 pub mod account;
+pub mod audit;
 pub mod core;
+pub mod invitation;
+pub mod notification;
+pub mod organization;
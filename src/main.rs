@@ -1,6 +1,7 @@
-use unnamed::core::App;
+use clap::Parser;
+use unnamed::core::cli::Cli;
 
 #[tokio::main]
 async fn main() {
-    App::serve().await
+    Cli::parse().run().await
 }
@@ -0,0 +1,48 @@
+use sqlx::SqliteConnection;
+
+use crate::core::error::{ApiError, ApiResult};
+
+pub async fn has_known_devices(connection: &mut SqliteConnection, user_id: i64) -> ApiResult<bool> {
+    sqlx::query!(
+        "SELECT COUNT(1) as count FROM known_devices WHERE user_id = ?",
+        user_id
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count > 0)
+    .map_err(ApiError::from)
+}
+
+// Returns whether the device was seen for the first time.
+pub async fn remember_device(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    fingerprint: &str,
+) -> ApiResult<bool> {
+    let updated = sqlx::query!(
+        "
+        UPDATE known_devices
+        SET last_seen_at = DATETIME('subsec')
+        WHERE user_id = ? AND fingerprint = ?
+        ",
+        user_id,
+        fingerprint
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(ApiError::from)?;
+
+    if updated.rows_affected() > 0 {
+        return Ok(false);
+    }
+
+    sqlx::query!(
+        "INSERT INTO known_devices (user_id, fingerprint) VALUES (?, ?)",
+        user_id,
+        fingerprint
+    )
+    .execute(connection)
+    .await
+    .map(|_| true)
+    .map_err(ApiError::from)
+}
@@ -0,0 +1,2 @@
+pub mod known_device_repository;
+pub mod notification_repository;
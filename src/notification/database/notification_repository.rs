@@ -0,0 +1,217 @@
+use sqlx::SqliteConnection;
+
+use crate::{
+    core::{
+        error::{ApiError, ApiResult},
+        types::DbDateTime,
+    },
+    notification::entities::notification::{
+        CreateNotificationEntity, NotificationType, PendingNotificationEntity,
+    },
+};
+
+pub async fn create_notification(
+    connection: &mut SqliteConnection,
+    notification: CreateNotificationEntity,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "
+        INSERT INTO notifications (user_id, email, notification_type, details)
+        VALUES (?, ?, ?, ?)
+        ",
+        notification.user_id,
+        notification.email,
+        notification.notification_type,
+        notification.details
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn find_pending_notifications(
+    connection: &mut SqliteConnection,
+    max_attempts: i64,
+    limit: i64,
+) -> ApiResult<Vec<PendingNotificationEntity>> {
+    sqlx::query_as!(
+        PendingNotificationEntity,
+        r#"
+        SELECT
+            n.id,
+            n.created_at as "created_at: DbDateTime",
+            n.notification_type as "notification_type: NotificationType",
+            COALESCE(n.email, u.email) as "email!: String",
+            n.details,
+            n.attempts
+        FROM notifications n
+        LEFT JOIN users u ON u.id = n.user_id
+        WHERE n.sent_at IS NULL
+          AND n.attempts < ?
+        ORDER BY n.id
+        LIMIT ?
+        "#,
+        max_attempts,
+        limit
+    )
+    .fetch_all(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn mark_notification_sent(
+    connection: &mut SqliteConnection,
+    id: i64,
+    clear_details: bool,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "
+        UPDATE notifications
+        SET
+            sent_at = DATETIME('subsec'),
+            attempts = attempts + 1,
+            last_error = NULL,
+            details = CASE WHEN ? THEN NULL ELSE details END
+        WHERE id = ?
+        ",
+        clear_details,
+        id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+// Details are cleared with the last attempt when asked to, so a secret is not kept once the
+// notification is given up on.
+pub async fn record_failed_delivery(
+    connection: &mut SqliteConnection,
+    id: i64,
+    error: &str,
+    max_attempts: i64,
+    clear_details: bool,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "
+        UPDATE notifications
+        SET
+            attempts = attempts + 1,
+            last_error = ?,
+            details = CASE WHEN ? AND attempts + 1 >= ? THEN NULL ELSE details END
+        WHERE id = ?
+        ",
+        error,
+        clear_details,
+        max_attempts,
+        id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+// Gives up on unsent notifications created before `created_before` and clears their details.
+pub async fn expire_unsent_notifications(
+    connection: &mut SqliteConnection,
+    notification_type: NotificationType,
+    created_before: DbDateTime,
+    max_attempts: i64,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "
+        UPDATE notifications
+        SET
+            details = NULL,
+            attempts = MAX(attempts, ?),
+            last_error = COALESCE(last_error, 'Expired before it could be sent')
+        WHERE notification_type = ?
+          AND sent_at IS NULL
+          AND details IS NOT NULL
+          AND created_at < ?
+        ",
+        max_attempts,
+        notification_type,
+        created_before
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn find_opt_outs_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+) -> ApiResult<Vec<NotificationType>> {
+    sqlx::query!(
+        r#"
+        SELECT notification_type as "notification_type: NotificationType"
+        FROM notification_opt_outs
+        WHERE user_id = ?
+        "#,
+        user_id
+    )
+    .fetch_all(connection)
+    .await
+    .map(|results| {
+        results
+            .into_iter()
+            .map(|result| result.notification_type)
+            .collect()
+    })
+    .map_err(ApiError::from)
+}
+
+pub async fn is_opted_out(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    notification_type: NotificationType,
+) -> ApiResult<bool> {
+    sqlx::query!(
+        "
+        SELECT COUNT(1) as count
+        FROM notification_opt_outs
+        WHERE user_id = ? AND notification_type = ?
+        ",
+        user_id,
+        notification_type
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count > 0)
+    .map_err(ApiError::from)
+}
+
+pub async fn replace_opt_outs(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+    opt_outs: &[NotificationType],
+) -> ApiResult<()> {
+    sqlx::query!(
+        "DELETE FROM notification_opt_outs WHERE user_id = ?",
+        user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(ApiError::from)?;
+
+    for notification_type in opt_outs {
+        sqlx::query!(
+            "
+            INSERT INTO notification_opt_outs (user_id, notification_type)
+            VALUES (?, ?)
+            ON CONFLICT DO NOTHING
+            ",
+            user_id,
+            notification_type
+        )
+        .execute(&mut *connection)
+        .await
+        .map_err(ApiError::from)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::{SqliteConnection, SqlitePool};
+use strum::IntoEnumIterator;
+use tokio::task::JoinHandle;
+
+use crate::{
+    account::utils::hash_token,
+    core::{
+        error::{ApiError, ApiResult},
+        types::DbDateTime,
+        AppConfig, AppState,
+    },
+    notification::{
+        database::{known_device_repository, notification_repository},
+        entities::notification::{
+            CreateNotificationEntity, NotificationType, PendingNotificationEntity,
+        },
+        mailer::Mailer,
+    },
+};
+
+const DISPATCH_BATCH_SIZE: i64 = 50;
+
+pub async fn notify(
+    connection: &mut SqliteConnection,
+    config: &AppConfig,
+    notification: CreateNotificationEntity,
+) -> ApiResult<()> {
+    // Critical notifications carry OTPs and invitations the user can't get
+    // any other way, so only the optional ones are dropped when disabled.
+    if !config.notification.enabled && !notification.notification_type.is_critical() {
+        return Ok(());
+    }
+
+    if let Some(user_id) = notification
+        .user_id
+        .filter(|_| !notification.notification_type.is_critical())
+    {
+        if notification_repository::is_opted_out(
+            &mut *connection,
+            user_id,
+            notification.notification_type,
+        )
+        .await?
+        {
+            return Ok(());
+        }
+    }
+
+    notification_repository::create_notification(connection, notification).await
+}
+
+// Only notifies once per device, and not for the very first device a user signs in from.
+pub async fn notify_new_login(
+    connection: &mut SqliteConnection,
+    config: &AppConfig,
+    user_id: i64,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> ApiResult<()> {
+    let fingerprint = device_fingerprint(ip_address.as_deref(), user_agent.as_deref());
+    let has_known_devices = known_device_repository::has_known_devices(connection, user_id).await?;
+    let is_new_device =
+        known_device_repository::remember_device(connection, user_id, &fingerprint).await?;
+
+    if !has_known_devices || !is_new_device {
+        return Ok(());
+    }
+
+    notify(
+        connection,
+        config,
+        CreateNotificationEntity::new(NotificationType::NewLogin, user_id).with_details(json!({
+            "ipAddress": ip_address,
+            "userAgent": user_agent,
+        })),
+    )
+    .await
+}
+
+fn device_fingerprint(ip_address: Option<&str>, user_agent: Option<&str>) -> String {
+    hash_token(&format!(
+        "{}|{}",
+        ip_address.unwrap_or_default(),
+        user_agent.unwrap_or_default()
+    ))
+}
+
+pub struct NotificationDispatcher {
+    pool: SqlitePool,
+    mailer: Mailer,
+    config: Arc<AppConfig>,
+}
+
+impl NotificationDispatcher {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            pool: state.pool.clone(),
+            mailer: Mailer::new(&state.config.notification),
+            config: state.config.clone(),
+        }
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let period = self
+                .config
+                .notification
+                .poll_interval
+                .to_std()
+                .unwrap_or(Duration::from_secs(10));
+            let mut interval = tokio::time::interval(period);
+
+            loop {
+                interval.tick().await;
+
+                if let Err(err) = self.dispatch_pending().await {
+                    tracing::error!("Failed to dispatch notifications: {err:?}");
+                }
+            }
+        })
+    }
+
+    async fn dispatch_pending(&self) -> ApiResult<()> {
+        let max_attempts = self.config.notification.max_attempts;
+        let mut connection = self.pool.acquire().await.map_err(ApiError::from)?;
+
+        for notification_type in NotificationType::iter() {
+            if let Some(lifetime) = notification_type.secret_lifetime(&self.config) {
+                notification_repository::expire_unsent_notifications(
+                    &mut connection,
+                    notification_type,
+                    DbDateTime(Utc::now() - lifetime),
+                    max_attempts,
+                )
+                .await?;
+            }
+        }
+
+        let notifications = notification_repository::find_pending_notifications(
+            &mut connection,
+            max_attempts,
+            DISPATCH_BATCH_SIZE,
+        )
+        .await?;
+
+        for notification in notifications {
+            let subject = notification.notification_type.subject();
+            let body = render_body(&notification);
+
+            match self.mailer.send(&notification.email, subject, body).await {
+                Ok(()) => {
+                    notification_repository::mark_notification_sent(
+                        &mut connection,
+                        notification.id,
+                        notification.notification_type.has_secret_details(),
+                    )
+                    .await?
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to send notification {} (attempt {}): {err}",
+                        notification.id,
+                        notification.attempts + 1
+                    );
+                    notification_repository::record_failed_delivery(
+                        &mut connection,
+                        notification.id,
+                        &err,
+                        max_attempts,
+                        notification.notification_type.has_secret_details(),
+                    )
+                    .await?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn render_body(notification: &PendingNotificationEntity) -> String {
+    let details = notification
+        .details
+        .as_deref()
+        .and_then(|details| serde_json::from_str::<serde_json::Value>(details).ok())
+        .unwrap_or_default();
+    let detail = |key: &str| {
+        details
+            .get(key)
+            .and_then(|value| value.as_str())
+            .unwrap_or("unknown")
+            .to_string()
+    };
+    let time = DateTime::<Utc>::from(notification.created_at.clone()).to_rfc2822();
+
+    match notification.notification_type {
+        NotificationType::NewLogin => format!(
+            "We noticed a new sign-in to your account on {time}.\n\n\
+             IP address: {}\nDevice: {}\n\n\
+             If this was you, you can ignore this email. Otherwise, reset your password immediately.",
+            detail("ipAddress"),
+            detail("userAgent"),
+        ),
+        NotificationType::PasswordChanged => format!(
+            "The password for your account was changed on {time}.\n\n\
+             If you did not make this change, reset your password immediately."
+        ),
+        NotificationType::AccountLocked => format!(
+            "Your account was locked on {time} after too many failed sign-in attempts.\n\n\
+             You can unlock it by resetting your password."
+        ),
+        NotificationType::Invitation => format!(
+            "{}\n\n\
+             Accept the invitation with the following code before {}:\n\n{}\n\n\
+             If you were not expecting this invitation, you can ignore this email.",
+            details
+                .get("organization")
+                .and_then(|value| value.as_str())
+                .map_or("You have been invited.".to_string(), |organization| {
+                    format!("You have been invited to join {organization}.")
+                }),
+            detail("expiresAt"),
+            detail("token"),
+        ),
+        NotificationType::PasswordResetOtp => format!(
+            "Your password reset code is {}. It expires at {}.\n\n\
+             If you did not request a password reset, you can ignore this email.",
+            detail("otp"),
+            detail("expiresAt"),
+        ),
+    }
+}
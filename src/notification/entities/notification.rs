@@ -0,0 +1,106 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use strum::{Display, EnumIter};
+
+use chrono::Duration;
+
+use crate::core::{types::DbDateTime, AppConfig};
+
+#[derive(
+    Clone, Copy, PartialEq, Debug, Type, Serialize, Deserialize, JsonSchema, EnumIter, Display,
+)]
+pub enum NotificationType {
+    NewLogin,
+    PasswordChanged,
+    AccountLocked,
+    PasswordResetOtp,
+    Invitation,
+}
+
+impl NotificationType {
+    // Critical notices are always sent, users can only opt out of the others.
+    pub fn is_critical(&self) -> bool {
+        match self {
+            NotificationType::NewLogin => false,
+            NotificationType::PasswordChanged
+            | NotificationType::AccountLocked
+            | NotificationType::PasswordResetOtp
+            | NotificationType::Invitation => true,
+        }
+    }
+
+    // Details holding a secret are cleared once the notification has been sent, given up on, or
+    // has outlived the secret.
+    pub fn has_secret_details(&self) -> bool {
+        matches!(
+            self,
+            NotificationType::PasswordResetOtp | NotificationType::Invitation
+        )
+    }
+
+    // How long the secret in the details can be used, an unsent notification is useless after.
+    pub fn secret_lifetime(&self, config: &AppConfig) -> Option<Duration> {
+        match self {
+            NotificationType::PasswordResetOtp => Some(config.otp_validity_duration),
+            NotificationType::Invitation => Some(config.invitation.validity_duration),
+            NotificationType::NewLogin
+            | NotificationType::PasswordChanged
+            | NotificationType::AccountLocked => None,
+        }
+    }
+
+    pub fn subject(&self) -> &'static str {
+        match self {
+            NotificationType::NewLogin => "New sign-in to your account",
+            NotificationType::PasswordChanged => "Your password was changed",
+            NotificationType::AccountLocked => "Your account has been locked",
+            NotificationType::PasswordResetOtp => "Your password reset code",
+            NotificationType::Invitation => "You have been invited",
+        }
+    }
+}
+
+// Notifications go to a user, or to a bare email for people who do not have an account yet.
+pub struct CreateNotificationEntity {
+    pub user_id: Option<i64>,
+    pub email: Option<String>,
+    pub notification_type: NotificationType,
+    pub details: Option<String>,
+}
+
+impl CreateNotificationEntity {
+    pub fn new(notification_type: NotificationType, user_id: i64) -> Self {
+        Self {
+            user_id: Some(user_id),
+            email: None,
+            notification_type,
+            details: None,
+        }
+    }
+
+    pub fn to_email(notification_type: NotificationType, email: String) -> Self {
+        Self {
+            user_id: None,
+            email: Some(email),
+            notification_type,
+            details: None,
+        }
+    }
+
+    pub fn with_details(self, details: serde_json::Value) -> Self {
+        Self {
+            details: Some(details.to_string()),
+            ..self
+        }
+    }
+}
+
+pub struct PendingNotificationEntity {
+    pub id: i64,
+    pub created_at: DbDateTime,
+    pub notification_type: NotificationType,
+    pub email: String,
+    pub details: Option<String>,
+    pub attempts: i64,
+}
@@ -0,0 +1,57 @@
+use axum::extract::State;
+
+use crate::{
+    account::{
+        error::AccountError,
+        utils::extractors::{CurrentUser, ManageNotifications, RequirePermission},
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        extractors::{JsonResponse, ValidJsonRequest},
+        AppState,
+    },
+    notification::{
+        database::notification_repository,
+        models::{
+            request::notification::UpdateNotificationPreferencesRequest,
+            response::notification::NotificationPreferenceResponse,
+        },
+    },
+};
+
+#[axum::debug_handler]
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    CurrentUser(user, _): CurrentUser,
+    RequirePermission(_): RequirePermission<ManageNotifications>,
+) -> ApiResult<JsonResponse<Vec<NotificationPreferenceResponse>>> {
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+    notification_repository::find_opt_outs_by_user_id(&mut connection, user.id)
+        .await
+        .map(|opt_outs| NotificationPreferenceResponse::from_opt_outs(&opt_outs))
+        .map(JsonResponse)
+}
+
+#[axum::debug_handler]
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+    RequirePermission(_): RequirePermission<ManageNotifications>,
+    ValidJsonRequest(request): ValidJsonRequest<UpdateNotificationPreferencesRequest>,
+) -> ApiResult<JsonResponse<Vec<NotificationPreferenceResponse>>> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+    notification_repository::replace_opt_outs(&mut connection, user.id, &request.opt_outs).await?;
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(JsonResponse(NotificationPreferenceResponse::from_opt_outs(
+        &request.opt_outs,
+    )))
+}
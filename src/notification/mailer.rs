@@ -0,0 +1,62 @@
+use lettre::{message::Mailbox, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use strum::{Display, EnumIs, EnumString};
+
+use crate::core::NotificationConfig;
+
+#[derive(Clone, Copy, EnumString, EnumIs, Display)]
+pub enum MailerKind {
+    Log,
+    Smtp,
+}
+
+#[derive(Clone)]
+pub enum Mailer {
+    Log,
+    Smtp {
+        transport: AsyncSmtpTransport<Tokio1Executor>,
+        from: Mailbox,
+    },
+}
+
+impl Mailer {
+    pub fn new(config: &NotificationConfig) -> Self {
+        match config.mailer {
+            MailerKind::Log => Self::Log,
+            MailerKind::Smtp => {
+                let url = config.smtp_url.as_deref().unwrap_or_default();
+                let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(url)
+                    .unwrap_or_else(|err| panic!("Invalid SMTP_URL: {err}"))
+                    .build();
+                let from = config.from.parse().unwrap_or_else(|err| {
+                    panic!("Invalid NOTIFICATION_FROM {}: {err}", config.from)
+                });
+
+                Self::Smtp { transport, from }
+            }
+        }
+    }
+
+    pub async fn send(&self, to: &str, subject: &str, body: String) -> Result<(), String> {
+        match self {
+            Mailer::Log => {
+                tracing::info!("Sending email to {to} with subject \"{subject}\":\n{body}");
+                Ok(())
+            }
+            Mailer::Smtp { transport, from } => {
+                let to = to.parse::<Mailbox>().map_err(|err| err.to_string())?;
+                let message = Message::builder()
+                    .from(from.clone())
+                    .to(to)
+                    .subject(subject)
+                    .body(body)
+                    .map_err(|err| err.to_string())?;
+
+                transport
+                    .send(message)
+                    .await
+                    .map(|_| ())
+                    .map_err(|err| err.to_string())
+            }
+        }
+    }
+}
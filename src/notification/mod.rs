@@ -0,0 +1,9 @@
+pub mod database;
+pub mod dispatcher;
+pub mod entities;
+pub mod handlers;
+pub mod mailer;
+pub mod models;
+mod router;
+
+pub use router::*;
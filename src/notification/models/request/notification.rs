@@ -0,0 +1,41 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::{
+    core::{validators::Validatable, AppConfig},
+    notification::entities::notification::NotificationType,
+};
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationPreferencesRequest {
+    pub opt_outs: Vec<NotificationType>,
+}
+
+impl Validatable for UpdateNotificationPreferencesRequest {
+    fn validated_properties() -> Vec<String> {
+        vec!["optOuts".into()]
+    }
+
+    fn validate_property(&self, property: &str, _: &AppConfig) -> Option<Vec<String>> {
+        match property {
+            "optOuts" => {
+                let errors = self
+                    .opt_outs
+                    .iter()
+                    .filter(|notification_type| notification_type.is_critical())
+                    .map(|notification_type| {
+                        format!("{notification_type} notifications cannot be turned off.")
+                    })
+                    .collect::<Vec<String>>();
+
+                if errors.is_empty() {
+                    None
+                } else {
+                    Some(errors)
+                }
+            }
+            _ => None,
+        }
+    }
+}
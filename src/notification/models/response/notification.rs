@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::notification::entities::notification::NotificationType;
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferenceResponse {
+    pub notification_type: NotificationType,
+    pub critical: bool,
+    pub enabled: bool,
+}
+
+impl NotificationPreferenceResponse {
+    pub fn from_opt_outs(opt_outs: &[NotificationType]) -> Vec<Self> {
+        NotificationType::iter()
+            .map(|notification_type| Self {
+                notification_type,
+                critical: notification_type.is_critical(),
+                enabled: !opt_outs.contains(&notification_type),
+            })
+            .collect()
+    }
+}
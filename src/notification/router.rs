@@ -0,0 +1,25 @@
+use aide::axum::{routing::get, ApiRouter};
+
+use crate::core::{
+    constants::openapi::{tags::NOTIFICATION_TAG, DEFAULT_SECURITY_SCHEME},
+    AppState,
+};
+
+use super::handlers;
+
+pub fn router(state: AppState) -> ApiRouter {
+    ApiRouter::new().nest(
+        "/notification",
+        ApiRouter::new()
+            .api_route_with(
+                "/preferences",
+                get(handlers::preferences::get_notification_preferences)
+                    .put(handlers::preferences::update_notification_preferences),
+                |op| {
+                    op.tag(NOTIFICATION_TAG)
+                        .security_requirement(DEFAULT_SECURITY_SCHEME)
+                },
+            )
+            .with_state(state.clone()),
+    )
+}
@@ -0,0 +1,211 @@
+use sqlx::SqliteConnection;
+
+use crate::{
+    core::{
+        error::{ApiError, ApiResult},
+        types::DbDateTime,
+    },
+    organization::entities::organization::{
+        CreateOrganizationEntity, MemberEntity, OrganizationEntity, OrganizationRole,
+        UserOrganizationEntity,
+    },
+};
+
+pub async fn find_organization_by_id(
+    connection: &mut SqliteConnection,
+    id: i64,
+) -> ApiResult<Option<OrganizationEntity>> {
+    sqlx::query_as!(
+        OrganizationEntity,
+        r#"
+        SELECT id, created_at as "created_at: DbDateTime", name, slug
+        FROM organizations
+        WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_optional(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn organization_exists_by_slug(
+    connection: &mut SqliteConnection,
+    slug: &str,
+) -> ApiResult<bool> {
+    sqlx::query!(
+        "SELECT COUNT(1) as count FROM organizations WHERE slug = ?",
+        slug
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count > 0)
+    .map_err(ApiError::from)
+}
+
+pub async fn create_organization(
+    connection: &mut SqliteConnection,
+    organization: CreateOrganizationEntity,
+) -> ApiResult<OrganizationEntity> {
+    sqlx::query_as!(
+        OrganizationEntity,
+        r#"
+        INSERT INTO organizations (name, slug)
+        VALUES (?, ?)
+        RETURNING id, created_at as "created_at: DbDateTime", name, slug
+        "#,
+        organization.name,
+        organization.slug
+    )
+    .fetch_one(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn find_organizations_by_user_id(
+    connection: &mut SqliteConnection,
+    user_id: i64,
+) -> ApiResult<Vec<UserOrganizationEntity>> {
+    sqlx::query_as!(
+        UserOrganizationEntity,
+        r#"
+        SELECT
+            o.id,
+            o.created_at as "created_at: DbDateTime",
+            o.name,
+            o.slug,
+            m.role as "role: OrganizationRole"
+        FROM organization_members m
+        INNER JOIN organizations o ON o.id = m.organization_id
+        WHERE m.user_id = ?
+        ORDER BY o.name
+        "#,
+        user_id
+    )
+    .fetch_all(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn find_member_role(
+    connection: &mut SqliteConnection,
+    organization_id: i64,
+    user_id: i64,
+) -> ApiResult<Option<OrganizationRole>> {
+    sqlx::query!(
+        r#"
+        SELECT role as "role: OrganizationRole"
+        FROM organization_members
+        WHERE organization_id = ? AND user_id = ?
+        "#,
+        organization_id,
+        user_id
+    )
+    .fetch_optional(connection)
+    .await
+    .map(|result| result.map(|result| result.role))
+    .map_err(ApiError::from)
+}
+
+pub async fn find_members(
+    connection: &mut SqliteConnection,
+    organization_id: i64,
+) -> ApiResult<Vec<MemberEntity>> {
+    sqlx::query_as!(
+        MemberEntity,
+        r#"
+        SELECT
+            m.user_id,
+            u.email,
+            m.role as "role: OrganizationRole",
+            m.created_at as "created_at: DbDateTime"
+        FROM organization_members m
+        INNER JOIN users u ON u.id = m.user_id
+        WHERE m.organization_id = ?
+        ORDER BY m.created_at
+        "#,
+        organization_id
+    )
+    .fetch_all(connection)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn add_member(
+    connection: &mut SqliteConnection,
+    organization_id: i64,
+    user_id: i64,
+    role: OrganizationRole,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "INSERT INTO organization_members (organization_id, user_id, role) VALUES (?, ?, ?)",
+        organization_id,
+        user_id,
+        role
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn update_member_role(
+    connection: &mut SqliteConnection,
+    organization_id: i64,
+    user_id: i64,
+    role: OrganizationRole,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "UPDATE organization_members SET role = ? WHERE organization_id = ? AND user_id = ?",
+        role,
+        organization_id,
+        user_id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn remove_member(
+    connection: &mut SqliteConnection,
+    organization_id: i64,
+    user_id: i64,
+) -> ApiResult<()> {
+    sqlx::query!(
+        "DELETE FROM organization_members WHERE organization_id = ? AND user_id = ?",
+        organization_id,
+        user_id
+    )
+    .execute(&mut *connection)
+    .await
+    .map_err(ApiError::from)?;
+
+    sqlx::query!(
+        "
+        UPDATE sessions
+        SET active_organization_id = NULL
+        WHERE user_id = ? AND active_organization_id = ?
+        ",
+        user_id,
+        organization_id
+    )
+    .execute(connection)
+    .await
+    .map(|_| ())
+    .map_err(ApiError::from)
+}
+
+pub async fn count_owners(
+    connection: &mut SqliteConnection,
+    organization_id: i64,
+) -> ApiResult<i64> {
+    sqlx::query!(
+        "SELECT COUNT(1) as count FROM organization_members WHERE organization_id = ? AND role = 'Owner'",
+        organization_id
+    )
+    .fetch_one(connection)
+    .await
+    .map(|result| result.count)
+    .map_err(ApiError::from)
+}
@@ -0,0 +1,56 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use strum::Display;
+
+use crate::core::types::DbDateTime;
+
+#[derive(Clone, Copy, PartialEq, Debug, Type, Serialize, Deserialize, JsonSchema, Display)]
+pub enum OrganizationRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl OrganizationRole {
+    pub fn can_manage_members(&self) -> bool {
+        matches!(self, OrganizationRole::Owner | OrganizationRole::Admin)
+    }
+
+    // Admins manage regular members, only owners can grant or revoke ownership and admin rights.
+    pub fn can_grant(&self, role: OrganizationRole) -> bool {
+        match self {
+            OrganizationRole::Owner => true,
+            OrganizationRole::Admin => role == OrganizationRole::Member,
+            OrganizationRole::Member => false,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct OrganizationEntity {
+    pub id: i64,
+    pub created_at: DbDateTime,
+    pub name: String,
+    pub slug: String,
+}
+
+pub struct CreateOrganizationEntity {
+    pub name: String,
+    pub slug: String,
+}
+
+pub struct UserOrganizationEntity {
+    pub id: i64,
+    pub created_at: DbDateTime,
+    pub name: String,
+    pub slug: String,
+    pub role: OrganizationRole,
+}
+
+pub struct MemberEntity {
+    pub user_id: i64,
+    pub email: String,
+    pub role: OrganizationRole,
+    pub created_at: DbDateTime,
+}
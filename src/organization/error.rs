@@ -0,0 +1,65 @@
+use axum::http::StatusCode;
+use thiserror::Error;
+
+use crate::core::error::{ApiErrorResponse, ErrorCode, IntoApiErrorResponse};
+
+#[derive(Error, Debug)]
+pub enum OrganizationError {
+    #[error("Organization {0} does not exist.")]
+    OrganizationDoesNotExist(i64),
+
+    #[error("No active organization. Switch to an organization first.")]
+    NoActiveOrganization,
+
+    #[error("You are not a member of organization {0}.")]
+    NotAMember(i64),
+
+    #[error("Your role in this organization does not allow this action.")]
+    InsufficientOrganizationRole,
+
+    #[error("User {0} is already a member of this organization.")]
+    AlreadyAMember(String),
+
+    #[error("User {0} is not a member of this organization.")]
+    MemberDoesNotExist(i64),
+
+    #[error("An organization must keep at least one owner.")]
+    LastOwner,
+}
+
+impl ErrorCode for OrganizationError {
+    fn code(&self) -> &str {
+        match self {
+            OrganizationError::OrganizationDoesNotExist(_) => "ORG0001",
+            OrganizationError::NoActiveOrganization => "ORG0002",
+            OrganizationError::NotAMember(_) => "ORG0003",
+            OrganizationError::InsufficientOrganizationRole => "ORG0004",
+            OrganizationError::AlreadyAMember(_) => "ORG0005",
+            OrganizationError::MemberDoesNotExist(_) => "ORG0006",
+            OrganizationError::LastOwner => "ORG0007",
+        }
+    }
+}
+
+impl IntoApiErrorResponse for OrganizationError {
+    fn into_app_error_response(&self) -> ApiErrorResponse {
+        let status_code = match self {
+            OrganizationError::OrganizationDoesNotExist(_)
+            | OrganizationError::MemberDoesNotExist(_) => StatusCode::NOT_FOUND,
+            OrganizationError::NoActiveOrganization
+            | OrganizationError::AlreadyAMember(_)
+            | OrganizationError::LastOwner => StatusCode::BAD_REQUEST,
+            OrganizationError::NotAMember(_) | OrganizationError::InsufficientOrganizationRole => {
+                StatusCode::FORBIDDEN
+            }
+        };
+
+        ApiErrorResponse {
+            status_code,
+            code: self.code().into(),
+            message: self.to_string(),
+            debug_description: None,
+            validation_errors: vec![],
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use axum::{
+    extract::{Path, State},
+    response::NoContent,
+};
+use serde_json::json;
+
+use crate::{
+    account::{error::AccountError, utils::extractors::CurrentUser},
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        extractors::{ClientIp, JsonRequest, JsonResponse},
+        AppState,
+    },
+    organization::{
+        database::organization_repository,
+        entities::organization::OrganizationRole,
+        models::{
+            request::organization::UpdateMemberRoleRequest, response::organization::MemberResponse,
+        },
+        utils::extractors::CurrentOrg,
+    },
+};
+
+#[axum::debug_handler]
+pub async fn get_members(
+    State(state): State<AppState>,
+    CurrentOrg(organization, _): CurrentOrg,
+) -> ApiResult<JsonResponse<Vec<MemberResponse>>> {
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+    organization_repository::find_members(&mut connection, organization.id)
+        .await
+        .map(|members| members.into_iter().map(Into::into).collect())
+        .map(JsonResponse)
+}
+
+#[axum::debug_handler]
+pub async fn update_member_role(
+    State(state): State<AppState>,
+    CurrentUser(actor, impersonation): CurrentUser,
+    CurrentOrg(organization, actor_role): CurrentOrg,
+    ClientIp(ip_address): ClientIp,
+    Path(user_id): Path<i64>,
+    JsonRequest(request): JsonRequest<UpdateMemberRoleRequest>,
+) -> ApiResult<NoContent> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+    let role = validators::find_member_role(&mut connection, organization.id, user_id).await?;
+
+    validators::validate_can_grant(actor_role, role)?;
+    validators::validate_can_grant(actor_role, request.role)?;
+    if role == OrganizationRole::Owner && request.role != OrganizationRole::Owner {
+        validators::validate_not_last_owner(&mut connection, organization.id).await?;
+    }
+
+    organization_repository::update_member_role(
+        &mut connection,
+        organization.id,
+        user_id,
+        request.role,
+    )
+    .await?;
+
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(
+            AuditEventType::OrganizationMemberRoleChanged,
+            Some(user_id),
+            ip_address,
+        )
+        .with_actor(actor.id)
+        .with_details(json!({
+            "organizationId": organization.id,
+            "from": role,
+            "to": request.role,
+        })),
+    )
+    .await?;
+
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(NoContent)
+}
+
+// Members with the right role can remove others, and every member can leave by removing themself.
+#[axum::debug_handler]
+pub async fn remove_member(
+    State(state): State<AppState>,
+    CurrentUser(actor, impersonation): CurrentUser,
+    CurrentOrg(organization, actor_role): CurrentOrg,
+    ClientIp(ip_address): ClientIp,
+    Path(user_id): Path<i64>,
+) -> ApiResult<NoContent> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+    let role = validators::find_member_role(&mut connection, organization.id, user_id).await?;
+
+    if user_id != actor.id {
+        validators::validate_can_grant(actor_role, role)?;
+    }
+    if role == OrganizationRole::Owner {
+        validators::validate_not_last_owner(&mut connection, organization.id).await?;
+    }
+
+    organization_repository::remove_member(&mut connection, organization.id, user_id).await?;
+
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(
+            AuditEventType::OrganizationMemberRemoved,
+            Some(user_id),
+            ip_address,
+        )
+        .with_actor(actor.id)
+        .with_details(json!({ "organizationId": organization.id })),
+    )
+    .await?;
+
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(NoContent)
+}
+
+mod validators {
+    use sqlx::SqliteConnection;
+
+    use crate::{
+        core::error::{ApiError, ApiResult},
+        organization::{
+            database::organization_repository, entities::organization::OrganizationRole,
+            error::OrganizationError,
+        },
+    };
+
+    pub fn validate_can_grant(
+        actor_role: OrganizationRole,
+        role: OrganizationRole,
+    ) -> ApiResult<()> {
+        if actor_role.can_grant(role) {
+            Ok(())
+        } else {
+            Err(ApiError::OrganizationError(
+                OrganizationError::InsufficientOrganizationRole,
+            ))
+        }
+    }
+
+    pub async fn find_member_role(
+        connection: &mut SqliteConnection,
+        organization_id: i64,
+        user_id: i64,
+    ) -> ApiResult<OrganizationRole> {
+        organization_repository::find_member_role(connection, organization_id, user_id)
+            .await?
+            .ok_or(ApiError::OrganizationError(
+                OrganizationError::MemberDoesNotExist(user_id),
+            ))
+    }
+
+    pub async fn validate_not_last_owner(
+        connection: &mut SqliteConnection,
+        organization_id: i64,
+    ) -> ApiResult<()> {
+        if organization_repository::count_owners(connection, organization_id).await? <= 1 {
+            Err(ApiError::OrganizationError(OrganizationError::LastOwner))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use axum::{extract::State, response::NoContent};
+use serde_json::json;
+
+use crate::{
+    account::{
+        database::session_repository,
+        error::AccountError,
+        utils::extractors::{CurrentSession, CurrentUser},
+    },
+    audit::{
+        database::audit_repository,
+        entities::audit_event::{AuditEventType, CreateAuditEventEntity},
+    },
+    core::{
+        error::{ApiError, ApiResult},
+        extractors::{ClientIp, JsonRequest, JsonResponse, ValidJsonRequest},
+        AppState,
+    },
+    organization::{
+        database::organization_repository,
+        entities::organization::{CreateOrganizationEntity, OrganizationRole},
+        error::OrganizationError,
+        models::{
+            request::organization::{CreateOrganizationRequest, SwitchOrganizationRequest},
+            response::organization::OrganizationResponse,
+        },
+        utils::{extractors::CurrentOrg, unique_slug},
+    },
+};
+
+#[axum::debug_handler]
+pub async fn create_organization(
+    State(state): State<AppState>,
+    CurrentUser(user, impersonation): CurrentUser,
+    ClientIp(ip_address): ClientIp,
+    ValidJsonRequest(request): ValidJsonRequest<CreateOrganizationRequest>,
+) -> ApiResult<JsonResponse<OrganizationResponse>> {
+    if impersonation.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.begin().await.map_err(ApiError::from)?;
+
+    let name = request.name.trim().to_string();
+    let slug = unique_slug(&mut connection, &name).await?;
+    let organization = organization_repository::create_organization(
+        &mut connection,
+        CreateOrganizationEntity { name, slug },
+    )
+    .await?;
+    organization_repository::add_member(
+        &mut connection,
+        organization.id,
+        user.id,
+        OrganizationRole::Owner,
+    )
+    .await?;
+
+    audit_repository::create_audit_event(
+        &mut connection,
+        CreateAuditEventEntity::new(
+            AuditEventType::OrganizationCreated,
+            Some(user.id),
+            ip_address,
+        )
+        .with_details(json!({ "organizationId": organization.id })),
+    )
+    .await?;
+
+    connection.commit().await.map_err(ApiError::from)?;
+
+    Ok(JsonResponse(OrganizationResponse::new(
+        organization,
+        OrganizationRole::Owner,
+    )))
+}
+
+#[axum::debug_handler]
+pub async fn get_my_organizations(
+    State(state): State<AppState>,
+    CurrentUser(user, _): CurrentUser,
+) -> ApiResult<JsonResponse<Vec<OrganizationResponse>>> {
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+    organization_repository::find_organizations_by_user_id(&mut connection, user.id)
+        .await
+        .map(|organizations| organizations.into_iter().map(Into::into).collect())
+        .map(JsonResponse)
+}
+
+#[axum::debug_handler]
+pub async fn get_current_organization(
+    State(_): State<AppState>,
+    CurrentOrg(organization, role): CurrentOrg,
+) -> JsonResponse<OrganizationResponse> {
+    JsonResponse(OrganizationResponse::new(organization, role))
+}
+
+#[axum::debug_handler]
+pub async fn switch_organization(
+    State(state): State<AppState>,
+    CurrentSession(session): CurrentSession,
+    JsonRequest(request): JsonRequest<SwitchOrganizationRequest>,
+) -> ApiResult<NoContent> {
+    if session.impersonator_id.is_some() {
+        return Err(ApiError::AccountError(
+            AccountError::ForbiddenWhileImpersonating,
+        ));
+    }
+
+    let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+    if let Some(organization_id) = request.organization_id {
+        if organization_repository::find_member_role(
+            &mut connection,
+            organization_id,
+            session.user_id,
+        )
+        .await?
+        .is_none()
+        {
+            return Err(ApiError::OrganizationError(OrganizationError::NotAMember(
+                organization_id,
+            )));
+        }
+    }
+
+    session_repository::update_active_organization(
+        &mut connection,
+        session.id,
+        request.organization_id,
+    )
+    .await?;
+
+    Ok(NoContent)
+}
@@ -0,0 +1,47 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::{
+    core::{validators::Validatable, AppConfig},
+    organization::entities::organization::OrganizationRole,
+};
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+}
+
+impl Validatable for CreateOrganizationRequest {
+    fn validated_properties() -> Vec<String> {
+        vec!["name".into()]
+    }
+
+    fn validate_property(&self, property: &str, _: &AppConfig) -> Option<Vec<String>> {
+        match property {
+            "name" => {
+                let length = self.name.trim().chars().count();
+                if length == 0 || length > 128 {
+                    Some(vec![
+                        "Organization name must be between 1 and 128 characters long.".into(),
+                    ])
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchOrganizationRequest {
+    pub organization_id: Option<i64>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMemberRoleRequest {
+    pub role: OrganizationRole,
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::organization::entities::organization::{
+    MemberEntity, OrganizationEntity, OrganizationRole, UserOrganizationEntity,
+};
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationResponse {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub name: String,
+    pub slug: String,
+    pub role: OrganizationRole,
+}
+
+impl OrganizationResponse {
+    pub fn new(organization: OrganizationEntity, role: OrganizationRole) -> Self {
+        Self {
+            id: organization.id,
+            created_at: organization.created_at.into(),
+            name: organization.name,
+            slug: organization.slug,
+            role,
+        }
+    }
+}
+
+impl From<UserOrganizationEntity> for OrganizationResponse {
+    fn from(value: UserOrganizationEntity) -> Self {
+        Self {
+            id: value.id,
+            created_at: value.created_at.into(),
+            name: value.name,
+            slug: value.slug,
+            role: value.role,
+        }
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberResponse {
+    pub user_id: i64,
+    pub email: String,
+    pub role: OrganizationRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl From<MemberEntity> for MemberResponse {
+    fn from(value: MemberEntity) -> Self {
+        Self {
+            user_id: value.user_id,
+            email: value.email,
+            role: value.role,
+            joined_at: value.created_at.into(),
+        }
+    }
+}
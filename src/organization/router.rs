@@ -0,0 +1,53 @@
+use aide::axum::{
+    routing::{get, put},
+    ApiRouter,
+};
+
+use crate::core::{
+    constants::openapi::{tags::ORGANIZATION_TAG, DEFAULT_SECURITY_SCHEME},
+    AppState,
+};
+
+use super::handlers;
+
+pub fn router(state: AppState) -> ApiRouter {
+    ApiRouter::new().nest(
+        "/organization",
+        ApiRouter::new()
+            .api_route_with(
+                "/",
+                get(handlers::organization::get_my_organizations)
+                    .post(handlers::organization::create_organization),
+                |op| {
+                    op.tag(ORGANIZATION_TAG)
+                        .security_requirement(DEFAULT_SECURITY_SCHEME)
+                },
+            )
+            .api_route_with(
+                "/active",
+                get(handlers::organization::get_current_organization)
+                    .put(handlers::organization::switch_organization),
+                |op| {
+                    op.tag(ORGANIZATION_TAG)
+                        .security_requirement(DEFAULT_SECURITY_SCHEME)
+                },
+            )
+            .api_route_with(
+                "/active/members",
+                get(handlers::member::get_members),
+                |op| {
+                    op.tag(ORGANIZATION_TAG)
+                        .security_requirement(DEFAULT_SECURITY_SCHEME)
+                },
+            )
+            .api_route_with(
+                "/active/members/{user_id}",
+                put(handlers::member::update_member_role).delete(handlers::member::remove_member),
+                |op| {
+                    op.tag(ORGANIZATION_TAG)
+                        .security_requirement(DEFAULT_SECURITY_SCHEME)
+                },
+            )
+            .with_state(state.clone()),
+    )
+}
@@ -0,0 +1,59 @@
+use aide::OperationInput;
+use axum::extract::FromRequestParts;
+
+use crate::{
+    account::utils::extractors::resolve_session,
+    core::{error::ApiError, AppState},
+    organization::{
+        database::organization_repository,
+        entities::organization::{OrganizationEntity, OrganizationRole},
+        error::OrganizationError,
+    },
+};
+
+// The organization selected in the current session, along with the user's role in it.
+// Handlers pass its id to repository queries to scope them to the organization.
+pub struct CurrentOrg(pub OrganizationEntity, pub OrganizationRole);
+
+impl FromRequestParts<AppState> for CurrentOrg {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let session = resolve_session(parts, state).await?;
+        let Some(organization_id) = session.active_organization_id else {
+            return Err(ApiError::OrganizationError(
+                OrganizationError::NoActiveOrganization,
+            ));
+        };
+
+        let mut connection = state.pool.acquire().await.map_err(ApiError::from)?;
+
+        let Some(role) = organization_repository::find_member_role(
+            &mut connection,
+            organization_id,
+            session.user_id,
+        )
+        .await?
+        else {
+            return Err(ApiError::OrganizationError(OrganizationError::NotAMember(
+                organization_id,
+            )));
+        };
+
+        let Some(organization) =
+            organization_repository::find_organization_by_id(&mut connection, organization_id)
+                .await?
+        else {
+            return Err(ApiError::OrganizationError(
+                OrganizationError::OrganizationDoesNotExist(organization_id),
+            ));
+        };
+
+        Ok(Self(organization, role))
+    }
+}
+
+impl OperationInput for CurrentOrg {}
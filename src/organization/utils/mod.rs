@@ -0,0 +1,28 @@
+use sqlx::SqliteConnection;
+
+use crate::{core::error::ApiResult, organization::database::organization_repository};
+
+pub mod extractors;
+
+pub async fn unique_slug(connection: &mut SqliteConnection, name: &str) -> ApiResult<String> {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+    let slug = if slug.is_empty() {
+        "organization".to_string()
+    } else {
+        slug
+    };
+
+    let mut candidate = slug.clone();
+    let mut suffix = 2;
+    while organization_repository::organization_exists_by_slug(connection, &candidate).await? {
+        candidate = format!("{slug}-{suffix}");
+        suffix += 1;
+    }
+
+    Ok(candidate)
+}